@@ -0,0 +1,32 @@
+use base64::Engine;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::{Rng, thread_rng};
+
+fn random_buffers() -> Vec<Vec<u8>> {
+    [0x1_000, 0x10_000, 0x100_000].into_iter().map(|len| {
+        let mut arr = vec![0; len];
+        thread_rng().fill(arr.as_mut_slice());
+        arr
+    }).collect()
+}
+
+fn base64_benchmark(c: &mut Criterion) {
+    let buffers = random_buffers();
+
+    let mut group = c.benchmark_group("base64");
+
+    for arr in &buffers {
+        group.throughput(Throughput::Bytes(arr.len() as u64));
+        group.bench_with_input(BenchmarkId::new("gesist", arr.len()), arr, |b, arr| b.iter(|| {
+            gesist::encode_to_base64(black_box(arr.as_slice()))
+        }));
+        group.bench_with_input(BenchmarkId::new("raw", arr.len()), arr, |b, arr| b.iter(|| {
+            base64::prelude::BASE64_URL_SAFE.encode(black_box(arr.as_slice()))
+        }));
+    }
+
+    group.finish();
+}
+
+criterion_group!(base64_benches, base64_benchmark);
+criterion_main!(base64_benches);
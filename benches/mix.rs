@@ -1,17 +1,77 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use rand::{Rng, thread_rng};
 
 fn mix_benchmark(c: &mut Criterion) {
     println!("bench!");
 
-    let mut arr = [0; 0x100_000];
-    thread_rng().fill(arr.as_mut());
+    let mut group = c.benchmark_group("mix");
 
-    c.bench_function("mix", |b| b.iter(|| {
-        let mut m = gesist::mixer::Mixer::new_from_padder(gesist::padder::Padder::new(black_box(arr.as_slice())));
-        m.mix();
-    }));
+    for len in [0x1_000, 0x10_000, 0x100_000, 0x1_000_000] {
+        let mut arr = vec![0; len];
+        thread_rng().fill(arr.as_mut_slice());
+
+        group.throughput(Throughput::Bytes(len as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(len), &arr, |b, arr| b.iter(|| {
+            let mut m = gesist::mixer::Mixer::new_from_padder(gesist::padder::Padder::new(black_box(arr.as_slice())));
+            m.mix();
+        }));
+    }
+
+    group.finish();
+}
+
+/// A copy of `Mixer::block_be_rotl`, which is private.
+fn block_be_rotl(content: &mut [u8; 3], shift: usize) {
+    let remain = 8 - shift;
+    let carry = content[0] >> remain;
+    content[0] = (content[0] << shift) | (content[1] >> remain);
+    content[1] = (content[1] << shift) | (content[2] >> remain);
+    content[2] = (content[2] << shift) | carry;
+}
+
+/// The block rotation before the shift table, computing the shift of each block from its index.
+fn rotate_blocks_computed(content: &mut [u8]) {
+    for (row, block) in content.chunks_exact_mut(3).enumerate() {
+        block_be_rotl(block.try_into().unwrap(), (row * 2 + 1) & 0x7);
+    }
+}
+
+/// The block rotation with the shift table, as in `Mixer::rotate_blocks_left`.
+fn rotate_blocks_table(content: &mut [u8]) {
+    const ROTATION_SHIFTS: [usize; 4] = [1, 3, 5, 7];
+
+    let mut groups = content.chunks_exact_mut(3 * ROTATION_SHIFTS.len());
+    for group in &mut groups {
+        for (block, &shift) in group.chunks_exact_mut(3).zip(ROTATION_SHIFTS.iter()) {
+            block_be_rotl(block.try_into().unwrap(), shift);
+        }
+    }
+
+    for (block, &shift) in groups.into_remainder().chunks_exact_mut(3).zip(ROTATION_SHIFTS.iter()) {
+        block_be_rotl(block.try_into().unwrap(), shift);
+    }
+}
+
+fn rotate_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rotate");
+
+    for len in [0x1_000, 0x100_000] {
+        let mut arr = vec![0; len];
+        thread_rng().fill(arr.as_mut_slice());
+
+        group.throughput(Throughput::Bytes(len as u64));
+        group.bench_with_input(BenchmarkId::new("computed", len), &arr, |b, arr| {
+            let mut buf = arr.clone();
+            b.iter(|| rotate_blocks_computed(black_box(&mut buf)))
+        });
+        group.bench_with_input(BenchmarkId::new("table", len), &arr, |b, arr| {
+            let mut buf = arr.clone();
+            b.iter(|| rotate_blocks_table(black_box(&mut buf)))
+        });
+    }
+
+    group.finish();
 }
 
-criterion_group!(mix_benches, mix_benchmark);
+criterion_group!(mix_benches, mix_benchmark, rotate_benchmark);
 criterion_main!(mix_benches);
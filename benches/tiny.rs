@@ -0,0 +1,23 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::{Rng, thread_rng};
+
+fn tiny_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tiny");
+
+    for len in [1, 4, 8, 15, 16, 31] {
+        let mut arr = vec![0; len];
+        thread_rng().fill(arr.as_mut_slice());
+
+        group.bench_with_input(BenchmarkId::new("encode", len), &arr, |b, arr| b.iter(|| {
+            gesist::encode(black_box(arr.as_slice()))
+        }));
+        group.bench_with_input(BenchmarkId::new("decode", len), &gesist::encode(&arr), |b, encoded| b.iter(|| {
+            gesist::decode_ref(black_box(encoded))
+        }));
+    }
+
+    group.finish();
+}
+
+criterion_group!(tiny_benches, tiny_benchmark);
+criterion_main!(tiny_benches);
@@ -1,53 +1,128 @@
 use std::string::FromUtf8Error;
+use std::env;
+use std::ffi::OsString;
 use std::fs;
 use std::io::{self, Read, stdin, stdout, Write};
-use clap::{Args, CommandFactory, Parser, error::ErrorKind};
-use gesist::{decode_from_base64, encode_to_base64};
-use gesist::padder::PaddingValidationError;
+use std::process;
+use std::time::{Duration, Instant};
+use clap::{Args, CommandFactory, Parser, Subcommand, error::ErrorKind};
+use gesist::{decode_from_base64, encode, encode_to_base64};
+use gesist::padder::{leb128_size, Padder, PaddingValidationError};
 
 #[derive(Parser)]
-#[command(name = "gesist", arg_required_else_help = true)]
+#[command(name = "gesist", arg_required_else_help = true, subcommand_required = true)]
 #[command(version, about, long_about = None)]
 struct GesistCli {
+    #[command(subcommand)]
+    action: Action,
+    #[arg(long, global = true, help = "Report the time spent in encoding or decoding to stderr.")]
+    time: bool,
+}
+
+#[derive(Subcommand)]
+enum Action {
+    #[command(short_flag = 'e', long_flag = "encode", about = "Encode the file or input from stdin.")]
+    Encode(EncodeArgs),
+    #[command(short_flag = 'd', long_flag = "decode", about = "Decode the file or input from stdin.")]
+    Decode(DecodeArgs),
+    #[command(long_flag = "check-only", about = "Check that the file or input from stdin decodes, without any output on success.")]
+    Verify(InputArgs),
+}
+
+#[derive(Args)]
+struct EncodeArgs {
+    #[arg(long, help = "Report the block structure of the encoded data to stderr.")]
+    verbose: bool,
+    #[arg(long, help = "Report the input size, output size and expansion ratio to stderr.")]
+    echo: bool,
+    #[arg(long, conflicts_with = "c_array", help = "Print the raw encoded bytes as a Rust array literal instead of base64.")]
+    rust_array: bool,
+    #[arg(long, help = "Print the raw encoded bytes as a C array initializer instead of base64.")]
+    c_array: bool,
     #[command(flatten)]
-    main: MainActions,
-    #[arg(help = "File to be encoded or decoded, if not provided, stdin will be used.")]
-    file: Option<String>,
+    input: InputArgs,
 }
 
 #[derive(Args)]
-#[group(required = true, multiple = false)]
-struct MainActions {
-    #[arg(short = 'e', long, help = "Encode the file or input from stdin.")]
-    encode: bool,
-    #[arg(short = 'd', long, help = "Decode the file or input from stdin.")]
-    decode: bool,
+struct DecodeArgs {
+    #[arg(long, help = "Decode each non-empty line separately, writing the payloads separated by newlines.")]
+    lines: bool,
+    #[arg(long, requires = "lines", help = "Separate the payloads decoded with --lines by NUL bytes instead of newlines.")]
+    print0: bool,
+    #[arg(long, num_args = 1.., conflicts_with_all = ["file", "lines"], help = "Decode the given base64 instead of a file, joining multiple values.")]
+    text: Option<Vec<String>>,
+    #[command(flatten)]
+    input: InputArgs,
+}
+
+#[derive(Args)]
+struct InputArgs {
+    #[arg(help = "File to be processed, if not provided, stdin will be used.")]
+    file: Option<String>,
 }
 
 fn main() {
-    let args = GesistCli::parse();
+    let args = GesistCli::parse_from(mode_flag_first(env::args_os().collect()));
+
+    match args.action {
+        Action::Encode(encode) => {
+            let delimiters = match (encode.rust_array, encode.c_array) {
+                (true, _) => Some(("[", "]")),
+                (_, true) => Some(("{", "}")),
+                _ => None,
+            };
+            encode_once(encode.input.file, args.time, encode.verbose, encode.echo, delimiters)
+        }
+        Action::Decode(decode) if decode.lines => decode_lines(decode.input.file, args.time, if decode.print0 { b'\0' } else { b'\n' }),
+        Action::Decode(DecodeArgs { text: Some(text), .. }) => decode_once(text.concat().into_bytes(), args.time),
+        Action::Decode(decode) => decode_once(read_all_from_file_or_stdin(decode.input.file), args.time),
+        Action::Verify(input) => verify_once(read_all_from_file_or_stdin(input.file), args.time),
+    }
+}
 
-    match (args.main.encode, args.main.decode) {
-        (true, false) => encode_once(args.file),
-        (false, true) => decode_once(args.file),
-        _ => unreachable!(),
+// Before the modes became subcommands, `gesist FILE -e` was accepted as well as `gesist -e FILE`. Clap would take the
+// file for an unknown subcommand, so move a mode flag given after the file in front of it.
+fn mode_flag_first(mut args: Vec<OsString>) -> Vec<OsString> {
+    let is_mode_flag = |arg: &OsString| matches!(arg.to_str(), Some("-e" | "-d" | "--encode" | "--decode" | "--check-only"));
+    let Some(file) = args.iter().skip(1).position(|arg| !arg.to_string_lossy().starts_with('-')).map(|i| i + 1) else {
+        return args;
+    };
+    if GesistCli::command().find_subcommand(&args[file]).is_some() || args[1..file].iter().any(is_mode_flag) {
+        return args;
     }
+    if let Some(flag) = args[file..].iter().position(is_mode_flag).map(|i| i + file) {
+        let flag = args.remove(flag);
+        args.insert(1, flag);
+    }
+    args
+}
+
+// Exit codes for each kind of failure, so that scripts can tell them apart. Usage errors are reported by clap with
+// exit code 2.
+const EXIT_IO_ERROR: i32 = 3;
+const EXIT_UTF8_ERROR: i32 = 4;
+const EXIT_BASE64_ERROR: i32 = 5;
+const EXIT_DECODE_ERROR: i32 = 6;
+
+fn exit_with_error(kind: ErrorKind, message: String, code: i32) -> ! {
+    let _ = GesistCli::command().error(kind, message).print();
+    process::exit(code)
 }
 
 fn exit_on_io_error(error: io::Error) -> ! {
-    GesistCli::command().error(ErrorKind::Io, format!("IO Error: {}", error)).exit()
+    exit_with_error(ErrorKind::Io, format!("IO Error: {}", error), EXIT_IO_ERROR)
 }
 
 fn exit_on_from_utf8_error(error: FromUtf8Error) -> ! {
-    GesistCli::command().error(ErrorKind::InvalidUtf8, format!("FromUtf8 Error: {}", error)).exit()
+    exit_with_error(ErrorKind::InvalidUtf8, format!("FromUtf8 Error: {}", error), EXIT_UTF8_ERROR)
 }
 
 fn exit_on_base64_error(error: base64::DecodeError) -> ! {
-    GesistCli::command().error(ErrorKind::InvalidValue, format!("Base64 Error: {}", error)).exit()
+    exit_with_error(ErrorKind::InvalidValue, format!("Base64 Error: {}", error), EXIT_BASE64_ERROR)
 }
 
 fn exit_on_decode_error(error: PaddingValidationError) -> ! {
-    GesistCli::command().error(ErrorKind::InvalidValue, format!("Decode Error: {:?}, it's likely that the input is corrupted.", error)).exit()
+    exit_with_error(ErrorKind::InvalidValue, format!("Decode Error: {:?}, it's likely that the input is corrupted.", error), EXIT_DECODE_ERROR)
 }
 
 fn read_all_from_file_or_stdin(file: Option<String>) -> Vec<u8> {
@@ -67,13 +142,105 @@ fn whitespace_removed(mut input: String) -> String {
     input
 }
 
-fn encode_once(file: Option<String>) {
-    println!("{}", encode_to_base64(read_all_from_file_or_stdin(file)))
+fn bom_removed(input: String) -> String {
+    match input.strip_prefix('\u{feff}') {
+        Some(rest) => rest.to_owned(),
+        None => input,
+    }
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+fn report_time(action: &str, elapsed: Duration) {
+    eprintln!("{} time: {:.3} ms", action, elapsed.as_secs_f64() * 1000.0)
+}
+
+fn report_block_structure(payload_length: usize) {
+    let padded_size = Padder::padded_size(payload_length);
+    let length_field = leb128_size(payload_length);
+
+    eprintln!("Payload length: {}", payload_length);
+    eprintln!("Padded size: {}", padded_size);
+    eprintln!("Length field bytes: {}", length_field);
+    eprintln!("Checksum bytes: {}", padded_size - length_field - payload_length);
+}
+
+fn report_expansion(input_size: usize, output_size: usize) {
+    eprintln!("Input size: {}", input_size);
+    eprintln!("Output size: {}", output_size);
+    if input_size > 0 {
+        eprintln!("Expansion ratio: {:.3}", output_size as f64 / input_size as f64);
+    }
 }
 
-fn decode_once(file: Option<String>) {
+fn byte_array_literal(data: &[u8], (open, close): (&str, &str)) -> String {
+    let bytes: Vec<_> = data.iter().map(|byte| format!("0x{:02x}", byte)).collect();
+    format!("{}{}{}", open, bytes.join(", "), close)
+}
+
+fn encode_once(file: Option<String>, time: bool, verbose: bool, echo: bool, array_delimiters: Option<(&str, &str)>) {
     let content = read_all_from_file_or_stdin(file);
-    let stripped = String::from_utf8(content).map(whitespace_removed).unwrap_or_else(|e| exit_on_from_utf8_error(e));
-    let data = decode_from_base64(stripped).unwrap_or_else(|e| exit_on_base64_error(e)).unwrap_or_else(|e| exit_on_decode_error(e));
+    if verbose && !content.is_empty() {
+        report_block_structure(content.len());
+    }
+    let content_len = content.len();
+    // the output size reported by --echo is the size of the encoded bytes, not of the array literal
+    let (encoded, output_len, elapsed) = match array_delimiters {
+        Some(delimiters) => {
+            let (encoded, elapsed) = timed(|| encode(content));
+            (byte_array_literal(&encoded, delimiters), encoded.len(), elapsed)
+        }
+        None => {
+            let (encoded, elapsed) = timed(|| encode_to_base64(content));
+            let output_len = encoded.len();
+            (encoded, output_len, elapsed)
+        }
+    };
+    if time {
+        report_time("Encode", elapsed);
+    }
+    if echo {
+        report_expansion(content_len, output_len);
+    }
+    println!("{}", encoded)
+}
+
+fn decode_payload(content: Vec<u8>, time: bool) -> Box<[u8]> {
+    let stripped = String::from_utf8(content).map(bom_removed).map(whitespace_removed).unwrap_or_else(|e| exit_on_from_utf8_error(e));
+    let (decoded, elapsed) = timed(|| decode_from_base64(stripped));
+    if time {
+        report_time("Decode", elapsed);
+    }
+    decoded.unwrap_or_else(|e| exit_on_base64_error(e)).unwrap_or_else(|e| exit_on_decode_error(e))
+}
+
+fn decode_once(content: Vec<u8>, time: bool) {
+    let data = decode_payload(content, time);
     stdout().write_all(&data).unwrap_or_else(|e| exit_on_io_error(e))
 }
+
+fn decode_lines(file: Option<String>, time: bool, separator: u8) {
+    let content = read_all_from_file_or_stdin(file);
+    let content = String::from_utf8(content).map(bom_removed).unwrap_or_else(|e| exit_on_from_utf8_error(e));
+    let mut out = stdout().lock();
+    let mut total = Duration::ZERO;
+
+    for line in content.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let (decoded, elapsed) = timed(|| decode_from_base64(line));
+        total += elapsed;
+        let data = decoded.unwrap_or_else(|e| exit_on_base64_error(e)).unwrap_or_else(|e| exit_on_decode_error(e));
+        out.write_all(&data).and_then(|_| out.write_all(&[separator])).unwrap_or_else(|e| exit_on_io_error(e));
+    }
+
+    if time {
+        report_time("Decode", total);
+    }
+}
+
+fn verify_once(content: Vec<u8>, time: bool) {
+    decode_payload(content, time);
+}
@@ -6,6 +6,4 @@ fn main() {
         println!("Encoded: {}", s);
         println!("Decoded: {}", String::from_utf8(decode_from_base64(s.as_bytes()).unwrap().unwrap().into()).unwrap())
     }
-
-    return;
 }
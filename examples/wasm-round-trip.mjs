@@ -0,0 +1,22 @@
+// Round trip through the WebAssembly bindings from Node.js.
+//
+// Build the bindings first, from the repository root, with a wasm-bindgen CLI of the same version as the dependency:
+//
+//     cargo rustc --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib
+//     wasm-bindgen --target nodejs --out-dir pkg target/wasm32-unknown-unknown/release/gesist.wasm
+//
+// then run `node examples/wasm-round-trip.mjs`. In a browser, use `--target web` instead, and call the default export
+// of `pkg/gesist.js` to load the module before using the functions.
+
+import { encodeBase64, decodeBase64 } from "../pkg/gesist.js";
+
+const input = new TextEncoder().encode("hello, world");
+const encoded = encodeBase64(input);
+console.log(`Encoded: ${encoded}`);
+console.log(`Decoded: ${new TextDecoder().decode(decodeBase64(encoded))}`);
+
+try {
+    decodeBase64("AAAA");
+} catch (e) {
+    console.log(`Rejected corrupted input: ${e.message}`);
+}
@@ -0,0 +1,52 @@
+use crate::padder::PaddingValidationError;
+
+/// `Codec` is an object-safe interface for byte-to-byte codecs, so that gesist can be stored as a `Box<dyn Codec>`
+/// and swapped for other codecs.
+pub trait Codec {
+    /// The error returned when decoding fails.
+    type Error;
+
+    /// Encodes the input.
+    fn encode(&self, input: &[u8]) -> Box<[u8]>;
+
+    /// Decodes the input.
+    fn decode(&self, input: &[u8]) -> Result<Box<[u8]>, Self::Error>;
+}
+
+/// `GesistCodec` is a stateless `Codec` which delegates to `encode` and `decode`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct GesistCodec;
+
+impl GesistCodec {
+    /// Creates a new `GesistCodec`.
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// Implementation of the `Codec` trait for the `GesistCodec` struct.
+impl Codec for GesistCodec {
+    type Error = PaddingValidationError;
+
+    fn encode(&self, input: &[u8]) -> Box<[u8]> {
+        crate::encode(input)
+    }
+
+    fn decode(&self, input: &[u8]) -> Result<Box<[u8]>, Self::Error> {
+        crate::decode(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::encode;
+    use crate::codec::{Codec, GesistCodec};
+    use crate::padder::PaddingValidationError;
+
+    #[test]
+    fn codec_trait_object() {
+        let codec: Box<dyn Codec<Error = PaddingValidationError>> = Box::new(GesistCodec::new());
+        assert_eq!(codec.encode(b"a"), encode(b"a"));
+        assert_eq!(codec.decode(&codec.encode(b"hello")).unwrap().as_ref(), b"hello");
+    }
+}
@@ -0,0 +1,162 @@
+// A C-compatible interface to `encode` and `decode`, enabled by the `ffi` feature.
+//
+// The library has to be built as a C library to be linked from C, e.g. with
+// `cargo rustc --release --features ffi --crate-type cdylib` (or `staticlib`). A header can be generated with cbindgen,
+// e.g. `cbindgen --lang c --output gesist.h`, with `ffi` listed in the `[parse.expand] features` of its config so that
+// this module is visible to it.
+//
+// Every function returns a status code, which is `GESIST_OK` on success. Output buffers are allocated by gesist and
+// must be released with `gesist_free`.
+
+use std::ptr;
+use std::slice;
+
+use crate::padder::PaddingValidationErrorKind;
+
+/// The operation succeeded.
+pub const GESIST_OK: i32 = 0;
+/// A pointer argument which must not be null is null.
+pub const GESIST_ERR_NULL_POINTER: i32 = -1;
+/// `PaddingValidationError::NotAligned`.
+pub const GESIST_ERR_NOT_ALIGNED: i32 = 1;
+/// `PaddingValidationError::BadLengthField`.
+pub const GESIST_ERR_BAD_LENGTH_FIELD: i32 = 2;
+/// `PaddingValidationError::UnexpectedPaddedLength`.
+pub const GESIST_ERR_UNEXPECTED_PADDED_LENGTH: i32 = 3;
+/// `PaddingValidationError::InvalidChecksum`.
+pub const GESIST_ERR_INVALID_CHECKSUM: i32 = 4;
+/// `PaddingValidationError::PayloadTooLarge`.
+pub const GESIST_ERR_PAYLOAD_TOO_LARGE: i32 = 5;
+/// `PaddingValidationError::TagMismatch`.
+pub const GESIST_ERR_TAG_MISMATCH: i32 = 6;
+/// `PaddingValidationError::UnexpectedPayloadLength`.
+pub const GESIST_ERR_UNEXPECTED_PAYLOAD_LENGTH: i32 = 7;
+
+/// Returns the status code reported for an error of the given kind.
+pub fn status_code(kind: PaddingValidationErrorKind) -> i32 {
+    match kind {
+        PaddingValidationErrorKind::NotAligned => GESIST_ERR_NOT_ALIGNED,
+        PaddingValidationErrorKind::BadLengthField => GESIST_ERR_BAD_LENGTH_FIELD,
+        PaddingValidationErrorKind::UnexpectedPaddedLength => GESIST_ERR_UNEXPECTED_PADDED_LENGTH,
+        PaddingValidationErrorKind::InvalidChecksum => GESIST_ERR_INVALID_CHECKSUM,
+        PaddingValidationErrorKind::PayloadTooLarge => GESIST_ERR_PAYLOAD_TOO_LARGE,
+        PaddingValidationErrorKind::TagMismatch => GESIST_ERR_TAG_MISMATCH,
+        PaddingValidationErrorKind::UnexpectedPayloadLength => GESIST_ERR_UNEXPECTED_PAYLOAD_LENGTH,
+    }
+}
+
+/// Reads the input buffer, accepting a null pointer for empty input.
+///
+/// # Safety
+///
+/// Unless `input` is null or `input_len` is 0, `input` must be valid for reads of `input_len` bytes.
+unsafe fn input_slice<'a>(input: *const u8, input_len: usize) -> Option<&'a [u8]> {
+    match (input.is_null(), input_len) {
+        (_, 0) => Some(&[]),
+        (true, _) => None,
+        (false, _) => Some(slice::from_raw_parts(input, input_len)),
+    }
+}
+
+/// Hands an output buffer over to the caller, who must release it with `gesist_free`. Empty output is returned as a
+/// null pointer.
+///
+/// # Safety
+///
+/// `out` and `out_len` must be valid for writes.
+unsafe fn write_output(data: Box<[u8]>, out: *mut *mut u8, out_len: *mut usize) {
+    *out_len = data.len();
+    *out = if data.is_empty() { ptr::null_mut() } else { Box::into_raw(data) as *mut u8 };
+}
+
+/// Encodes `input_len` bytes at `input`, storing a newly allocated buffer holding the encoded data in `*out` and its
+/// length in `*out_len`.
+///
+/// Returns `GESIST_OK`, or `GESIST_ERR_NULL_POINTER` if `out` or `out_len` is null, or `input` is null while
+/// `input_len` is not 0. Empty input gives empty output, for which `*out` is null.
+///
+/// # Safety
+///
+/// Unless `input` is null or `input_len` is 0, `input` must be valid for reads of `input_len` bytes. `out` and
+/// `out_len` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn gesist_encode(input: *const u8, input_len: usize, out: *mut *mut u8, out_len: *mut usize) -> i32 {
+    if out.is_null() || out_len.is_null() {
+        return GESIST_ERR_NULL_POINTER;
+    }
+
+    match input_slice(input, input_len) {
+        Some(input) => {
+            write_output(crate::encode(input), out, out_len);
+            GESIST_OK
+        }
+        None => GESIST_ERR_NULL_POINTER,
+    }
+}
+
+/// Decodes `input_len` bytes at `input`, storing a newly allocated buffer holding the payload in `*out` and its
+/// length in `*out_len`.
+///
+/// Returns `GESIST_OK`, `GESIST_ERR_NULL_POINTER` like `gesist_encode`, or the `GESIST_ERR_*` code of the
+/// `PaddingValidationError` if the input fails to decode, in which case `*out` and `*out_len` are left untouched.
+///
+/// # Safety
+///
+/// The same as `gesist_encode`.
+#[no_mangle]
+pub unsafe extern "C" fn gesist_decode(input: *const u8, input_len: usize, out: *mut *mut u8, out_len: *mut usize) -> i32 {
+    if out.is_null() || out_len.is_null() {
+        return GESIST_ERR_NULL_POINTER;
+    }
+
+    match input_slice(input, input_len).map(crate::decode_ref) {
+        Some(Ok(payload)) => {
+            write_output(payload, out, out_len);
+            GESIST_OK
+        }
+        Some(Err(e)) => status_code(e.kind()),
+        None => GESIST_ERR_NULL_POINTER,
+    }
+}
+
+/// Releases a buffer returned by `gesist_encode` or `gesist_decode`. Does nothing if `data` is null.
+///
+/// # Safety
+///
+/// `data` must be null, or a buffer returned by `gesist_encode` or `gesist_decode` which has not been released yet,
+/// and `len` must be the length returned with it.
+#[no_mangle]
+pub unsafe extern "C" fn gesist_free(data: *mut u8, len: usize) {
+    if !data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(data, len)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::encode;
+    use crate::ffi::{gesist_decode, gesist_encode, gesist_free, GESIST_ERR_NOT_ALIGNED, GESIST_ERR_NULL_POINTER, GESIST_OK};
+
+    #[test]
+    fn ffi_round_trip() {
+        let input = b"hello, world";
+        let (mut encoded, mut encoded_len) = (std::ptr::null_mut(), 0);
+        let (mut decoded, mut decoded_len) = (std::ptr::null_mut(), 0);
+        unsafe {
+            assert_eq!(gesist_encode(input.as_ptr(), input.len(), &mut encoded, &mut encoded_len), GESIST_OK);
+            assert_eq!(std::slice::from_raw_parts(encoded, encoded_len), encode(input).as_ref());
+
+            assert_eq!(gesist_decode(encoded, encoded_len, &mut decoded, &mut decoded_len), GESIST_OK);
+            assert_eq!(std::slice::from_raw_parts(decoded, decoded_len), input);
+            assert_eq!(gesist_decode(encoded, encoded_len - 1, &mut decoded, &mut decoded_len), GESIST_ERR_NOT_ALIGNED);
+
+            gesist_free(encoded, encoded_len);
+            gesist_free(decoded, decoded_len);
+
+            assert_eq!(gesist_encode(std::ptr::null(), 1, &mut encoded, &mut encoded_len), GESIST_ERR_NULL_POINTER);
+            assert_eq!(gesist_encode(std::ptr::null(), 0, &mut encoded, &mut encoded_len), GESIST_OK);
+            assert!(encoded.is_null());
+            assert_eq!(encoded_len, 0);
+        }
+    }
+}
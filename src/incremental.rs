@@ -0,0 +1,76 @@
+use crate::padder::PaddingValidationError;
+
+/// `IncrementalEncoder` collects the input of `encode` in pieces, and encodes it once all pieces are pushed.
+///
+/// As the length field precedes the payload, nothing can be encoded before the total length is known, so the pieces
+/// are buffered until `finalize`.
+#[derive(Clone, Debug, Default)]
+pub struct IncrementalEncoder {
+    buffer: Vec<u8>,
+    limit: Option<usize>,
+}
+
+impl IncrementalEncoder {
+    /// Creates a new `IncrementalEncoder` without a size limit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new `IncrementalEncoder` which rejects pieces that would make the input longer than `limit` bytes.
+    pub fn with_limit(limit: usize) -> Self {
+        Self { buffer: Vec::new(), limit: Some(limit) }
+    }
+
+    /// Appends a piece to the input.
+    ///
+    /// # Arguments
+    ///
+    /// * `piece` - The bytes to append.
+    ///
+    /// # Returns
+    ///
+    /// * A `Result` which is:
+    ///     - `Ok` if the piece is appended.
+    ///     - `Err` containing `PaddingValidationError::PayloadTooLarge` if the input would exceed the limit, in which
+    ///       case nothing is appended.
+    pub fn push(&mut self, piece: &[u8]) -> Result<(), PaddingValidationError> {
+        let declared = self.buffer.len().saturating_add(piece.len());
+        if let Some(limit) = self.limit.filter(|&limit| declared > limit) {
+            return Err(PaddingValidationError::PayloadTooLarge { declared, limit });
+        }
+
+        self.buffer.extend_from_slice(piece);
+        Ok(())
+    }
+
+    /// Returns the number of bytes pushed so far.
+    pub fn len_so_far(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Encodes all pieces pushed so far as one input, like `encode`.
+    pub fn finalize(self) -> Box<[u8]> {
+        crate::encode(self.buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::encode;
+    use crate::incremental::IncrementalEncoder;
+    use crate::padder::PaddingValidationError;
+
+    #[test]
+    fn incremental_encode() {
+        let mut encoder = IncrementalEncoder::new();
+        encoder.push(b"hello, ").unwrap();
+        encoder.push(b"world").unwrap();
+        assert_eq!(encoder.len_so_far(), 12);
+        assert_eq!(encoder.finalize(), encode(b"hello, world"));
+
+        let mut encoder = IncrementalEncoder::with_limit(8);
+        encoder.push(b"hello").unwrap();
+        assert_eq!(encoder.push(b", world"), Err(PaddingValidationError::PayloadTooLarge { declared: 12, limit: 8 }));
+        assert_eq!(encoder.len_so_far(), 5);
+    }
+}
@@ -1,58 +1,792 @@
+use std::borrow::{Borrow, Cow};
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
 use std::io::{self, Write};
+use std::ops::{Deref, Range};
 use base64::Engine;
+use base64::alphabet;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
 
 use mixer::Mixer;
-use padder::{Padder, PaddingValidationError};
+use padder::{read_length_prefix, Padder, PaddingValidationError};
+use transport::{Output, TransportDecodeError};
 
 pub mod padder;
 pub mod mixer;
+pub mod transport;
+pub mod stream;
+pub mod codec;
+pub mod incremental;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub mod wasm;
 
-fn do_encode(input: impl AsRef<[u8]>) -> Option<Mixer> {
+pub use padder::leb128_size;
+
+/// Calculates the size of the padded data block for a payload, which is also the size of the encoded data.
+///
+/// This is the same as `Padder::padded_size`, and is part of the stable public API.
+///
+/// # Example
+///
+/// ```
+/// use gesist::{encode, leb128_size, padded_size};
+///
+/// let payload = [0u8; 200];
+/// let length_field = leb128_size(payload.len());
+/// let checksum = padded_size(payload.len()) - length_field - payload.len();
+///
+/// assert_eq!((length_field, checksum), (2, 2));
+/// assert_eq!(encode(payload).len(), padded_size(payload.len()));
+/// ```
+///
+/// # Panics
+///
+/// Panics if the padded size does not fit in a usize. See `checked_padded_size` for a non-panicking version.
+pub const fn padded_size(input_size: usize) -> usize {
+    Padder::padded_size(input_size)
+}
+
+/// Calculates the size of the padded data block for a payload like `padded_size`, returning `None` on overflow.
+///
+/// This is the same as `Padder::checked_padded_size`.
+pub const fn checked_padded_size(input_size: usize) -> Option<usize> {
+    Padder::checked_padded_size(input_size)
+}
+
+/// Calculates the padded size in a u128, so that it cannot overflow for any usize payload length.
+fn wide_padded_size(payload_len: usize) -> u128 {
+    (payload_len as u128 + leb128_size(payload_len) as u128 + 1).next_multiple_of(Padder::ALIGNMENT as u128)
+}
+
+/// Calculates the ratio of the size of the encoded data to the size of the payload.
+///
+/// The relative overhead is largest for small payloads, e.g. 3.0 for a 1-byte payload, and approaches 1.0 for large
+/// ones. The ratio is infinite for an empty payload. It is defined even for lengths whose padded size does not fit in
+/// a usize.
+pub fn expansion_ratio(payload_len: usize) -> f64 {
+    wide_padded_size(payload_len) as f64 / payload_len as f64
+}
+
+/// Calculates the ratio of the size of the base64 output of `encode_to_base64` to the size of the payload.
+///
+/// As the encoded data is always a multiple of 3 bytes long, its base64 form has no padding, and this is exactly
+/// 4/3 of `expansion_ratio`.
+pub fn base64_expansion_ratio(payload_len: usize) -> f64 {
+    (wide_padded_size(payload_len) / 3 * 4) as f64 / payload_len as f64
+}
+
+/// A table of `padded_size(n)` for every `n` below 256, computed at compile time.
+///
+/// This allows sizing fixed arrays for small payloads in constant contexts, e.g. `[0u8; PADDED_SIZES[32]]`.
+pub const PADDED_SIZES: [usize; 256] = {
+    let mut table = [0; 256];
+    let mut n = 0;
+    while n < table.len() {
+        table[n] = padded_size(n);
+        n += 1;
+    }
+
+    table
+};
+
+fn do_encode_with(input: impl AsRef<[u8]>, mix_fn: impl FnOnce(&mut Mixer)) -> Option<Mixer> {
     let input = input.as_ref();
     if input.is_empty() {
+        #[cfg(feature = "metrics")]
+        metrics::emit(metrics::Event::Encode { input_len: 0, output_len: 0 });
         return None;
     }
 
-    let mut mix = Mixer::new_from_padder(Padder::new(input));
-    mix.mix();
+    Some(do_encode_padder(Padder::new(input), mix_fn))
+}
+
+/// Mixes an already padded data block, reporting it to the metrics hook like every other encode path.
+fn do_encode_padder(padder: Padder, mix_fn: impl FnOnce(&mut Mixer)) -> Mixer {
+    #[cfg(feature = "metrics")]
+    let input_len = padder.payload_length();
+
+    let mut mix = Mixer::new_from_padder(padder);
+    mix_fn(&mut mix);
+
+    #[cfg(feature = "metrics")]
+    metrics::emit(metrics::Event::Encode { input_len, output_len: mix.content_len() });
+    mix
+}
+
+/// Encodes a payload whose padded size is `N` on the stack, which `do_encode` uses for payloads of 1 to 15 bytes.
+///
+/// As the length is known at compile time, the mix is fully unrolled, and the only allocation is the one of the output.
+/// This saves about two thirds of the time of the generic path for such payloads, see the `tiny` benchmark.
+fn encode_tiny<const N: usize>(input: &[u8]) -> Box<[u8]> {
+    let mut block = [0; N];
+    block[0] = input.len() as u8;
+    block[1..=input.len()].copy_from_slice(input);
+    padder::fill_checksum(input, &mut block[1 + input.len()..]);
+    Mixer::mix_in_place(&mut block);
+
+    #[cfg(feature = "metrics")]
+    metrics::emit(metrics::Event::Encode { input_len: input.len(), output_len: N });
+    block.into()
+}
+
+/// Encodes the input, taking the stack-based path of `encode_tiny` for payloads of 1 to 15 bytes. Every encode entry
+/// point without a custom mix or filler goes through here.
+fn do_encode(input: &[u8]) -> Box<[u8]> {
+    macro_rules! dispatch_tiny {
+        ($input:expr, $($len:literal)*) => {
+            match $input.len() {
+                $($len => return encode_tiny::<{ PADDED_SIZES[$len] }>($input),)*
+                _ => {}
+            }
+        };
+    }
 
-    Some(mix)
+    dispatch_tiny!(input, 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15);
+    do_encode_with(input, Mixer::mix).map_or_else(|| [].into(), |m| m.into())
 }
 
 pub fn encode<T: AsRef<[u8]>>(input: T) -> Box<[u8]> {
-    do_encode(input).map_or_else(|| [].into(), |m| m.into())
+    do_encode(input.as_ref())
+}
+
+/// Encodes each input independently, in parallel if the `rayon` feature is enabled.
+pub fn encode_batch<T: AsRef<[u8]> + Sync>(inputs: &[T]) -> Vec<Box<[u8]>> {
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        inputs.par_iter().map(encode).collect()
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    {
+        inputs.iter().map(encode).collect()
+    }
+}
+
+/// Encodes the input like `encode`, returning a `Cow`.
+///
+/// Empty input is borrowed, as its encoding is also empty; any other input is encoded into owned data.
+pub fn encode_cow(input: &[u8]) -> Cow<'_, [u8]> {
+    let encoded = do_encode(input);
+    if encoded.is_empty() {
+        Cow::Borrowed(&[])
+    } else {
+        Cow::Owned(encoded.into_vec())
+    }
 }
 
 pub fn encode_to<T: AsRef<[u8]>, D: Write>(input: T, mut dest: D) -> io::Result<()> {
-    do_encode(input).map_or_else(|| Ok(()), |m| dest.write_all(m.as_slice()))
+    dest.write_all(&do_encode(input.as_ref()))
 }
 
 pub fn encode_to_base64<T: AsRef<[u8]>>(input: T) -> String {
-    do_encode(input).map_or_else(|| String::new(), |m| base64::prelude::BASE64_URL_SAFE.encode(m))
+    to_base64(&do_encode(input.as_ref()))
 }
 
-fn do_decode(place: impl Into<Box<[u8]>>) -> Result<Padder, PaddingValidationError> {
+/// Converts already mixed data to base64, using the same alphabet and padding as `encode_to_base64`.
+pub fn to_base64(mixed: &[u8]) -> String {
+    base64::prelude::BASE64_URL_SAFE.encode(mixed)
+}
+
+/// Converts base64 back into mixed data, using the same alphabet and padding as `decode_from_base64`. The result is
+/// not unmixed or validated.
+pub fn from_base64(s: &str) -> Result<Box<[u8]>, base64::DecodeError> {
+    Ok(base64::prelude::BASE64_URL_SAFE.decode(s)?.into_boxed_slice())
+}
+
+pub fn encode_with_output<T: AsRef<[u8]>>(input: T, output: Output) -> Vec<u8> {
+    match output {
+        Output::Raw => encode(input).into_vec(),
+        _ => output.encode(&encode(input)),
+    }
+}
+
+/// Encodes an already padded data block, consuming the `Padder`.
+///
+/// This is `encode` without the padding step, for callers who build and edit `Padder`s themselves. Filler is kept
+/// as it is in the `Padder`.
+pub fn encode_padder(padder: Padder) -> Box<[u8]> {
+    do_encode_padder(padder, Mixer::mix).into()
+}
+
+/// Encodes an already padded data block like `encode_padder`, cloning the `Padder` so that it can be reused.
+pub fn encode_padder_ref(padder: &Padder) -> Box<[u8]> {
+    encode_padder(padder.clone())
+}
+
+/// Encodes the input into a block of exactly `target_len` bytes, filling the space after the checksum with random
+/// bytes.
+///
+/// The true length of the input is kept in the length field, so `decode` recovers exactly the input.
+/// Returns `None` if `target_len` is not a multiple of `Padder::ALIGNMENT` or is smaller than the padded size of the
+/// input.
+///
+/// The random bytes come from `thread_rng`, see `encode_to_size_with_rng` to provide another RNG.
+#[cfg(feature = "rand")]
+pub fn encode_to_size<T: AsRef<[u8]>>(input: T, target_len: usize) -> Option<Box<[u8]>> {
+    encode_to_size_with_rng(input, target_len, &mut rand::thread_rng())
+}
+
+/// Encodes the input into a block of exactly `target_len` bytes like `encode_to_size`, taking the random bytes from
+/// `rng`.
+///
+/// A seeded RNG makes the output reproducible, e.g. in tests.
+#[cfg(feature = "rand")]
+pub fn encode_to_size_with_rng<T: AsRef<[u8]>>(input: T, target_len: usize, rng: &mut impl rand::RngCore) -> Option<Box<[u8]>> {
+    let input = input.as_ref();
+    let filler = target_len.checked_sub(Padder::padded_size(input.len()))?;
+    let mut padder = Padder::new_with_filler(input, filler)?;
+    rng.fill_bytes(padder.filler_mut());
+
+    Some(do_encode_padder(padder, Mixer::mix).into())
+}
+
+/// The number of random salt bytes added by `encode_salted`.
+#[cfg(feature = "rand")]
+pub const SALT_SIZE: usize = Padder::ALIGNMENT * 2;
+
+/// Encodes the input with `SALT_SIZE` random bytes following the checksum, so that encoding the same input twice
+/// gives different outputs.
+///
+/// The salt is part of the filler and is mixed with the rest of the block, while the length field still records the
+/// true length of the input. Decoding is salt-agnostic: `decode` skips the filler and recovers exactly the input.
+///
+/// The salt comes from `thread_rng`, see `encode_salted_with_rng` to provide another RNG.
+#[cfg(feature = "rand")]
+pub fn encode_salted<T: AsRef<[u8]>>(input: T) -> Box<[u8]> {
+    encode_salted_with_rng(input, &mut rand::thread_rng())
+}
+
+/// Encodes the input with `SALT_SIZE` random bytes like `encode_salted`, taking the salt from `rng`.
+#[cfg(feature = "rand")]
+pub fn encode_salted_with_rng<T: AsRef<[u8]>>(input: T, rng: &mut impl rand::RngCore) -> Box<[u8]> {
+    let input = input.as_ref();
+    encode_to_size_with_rng(input, Padder::padded_size(input.len()) + SALT_SIZE, rng).unwrap()
+}
+
+/// Encodes the input into a block of at least `min_len` bytes, filling the space after the checksum with random bytes
+/// if the input alone would give a shorter block.
+///
+/// The block length is `min_len` rounded up to a multiple of `Padder::ALIGNMENT`, or the padded size of the input if
+/// that is larger. The true length of the input is kept in the length field, so `decode` recovers exactly the input.
+///
+/// The random bytes come from `thread_rng`, see `encode_min_size_with_rng` to provide another RNG.
+#[cfg(feature = "rand")]
+pub fn encode_min_size<T: AsRef<[u8]>>(input: T, min_len: usize) -> Box<[u8]> {
+    encode_min_size_with_rng(input, min_len, &mut rand::thread_rng())
+}
+
+/// Encodes the input into a block of at least `min_len` bytes like `encode_min_size`, taking the random bytes from
+/// `rng`.
+#[cfg(feature = "rand")]
+pub fn encode_min_size_with_rng<T: AsRef<[u8]>>(input: T, min_len: usize, rng: &mut impl rand::RngCore) -> Box<[u8]> {
+    let input = input.as_ref();
+    let target_len = Padder::padded_size(input.len()).max(min_len.next_multiple_of(Padder::ALIGNMENT));
+    encode_to_size_with_rng(input, target_len, rng).unwrap()
+}
+
+/// The alignment of the blocks produced by `encode_simd_aligned`, suitable for aligned SIMD loads.
+pub const SIMD_ALIGNMENT: usize = 16;
+
+/// Encodes the input into a block whose length is a multiple of `SIMD_ALIGNMENT`.
+///
+/// As the block length must also be a multiple of `Padder::ALIGNMENT`, it is rounded up to a multiple of 48. The extra
+/// bytes are filler continuing the checksum sequence, and are skipped by `decode`, which recovers exactly the input.
+pub fn encode_simd_aligned<T: AsRef<[u8]>>(input: T) -> Box<[u8]> {
+    let input = input.as_ref();
+    if input.is_empty() {
+        return encode(input);
+    }
+
+    let padded_size = Padder::padded_size(input.len());
+    let filler = padded_size.next_multiple_of(SIMD_ALIGNMENT * Padder::ALIGNMENT) - padded_size;
+    let mut padder = Padder::new_with_filler(input, filler).unwrap();
+
+    let checksum_len = padder.checksum().len();
+    let crc = padder.checksum()[0];
+    for (i, byte) in padder.filler_mut().iter_mut().enumerate() {
+        *byte = crc.wrapping_add((checksum_len + i) as u8);
+    }
+
+    do_encode_padder(padder, Mixer::mix).into()
+}
+
+fn do_unmix_with(place: impl Into<Box<[u8]>>, mix_fn: impl FnOnce(&mut Mixer)) -> Result<Mixer, PaddingValidationError> {
     let place = place.into();
     let len = place.len();
+    if len == 0 {
+        return Err(PaddingValidationError::BadLengthField);
+    }
+
     let mut mix = Mixer::new(place).ok_or(PaddingValidationError::NotAligned { length: len })?;
-    mix.mix();
+    mix_fn(&mut mix);
 
-    Padder::try_from_raw(mix)
+    Ok(mix)
 }
 
+fn do_unmix(place: impl Into<Box<[u8]>>) -> Result<Mixer, PaddingValidationError> {
+    do_unmix_with(place, Mixer::mix)
+}
+
+fn do_decode(place: impl Into<Box<[u8]>>) -> Result<Padder, PaddingValidationError> {
+    do_decode_with(place, Mixer::mix, Padder::try_from_raw_with_filler)
+}
+
+/// Unmixes the input with `mix_fn` and validates it with `validate`, reporting the outcome to the metrics hook like
+/// every other decode path.
+fn do_decode_with(
+    place: impl Into<Box<[u8]>>,
+    mix_fn: impl FnOnce(&mut Mixer),
+    validate: impl FnOnce(Mixer) -> Result<Padder, PaddingValidationError>,
+) -> Result<Padder, PaddingValidationError> {
+    let place = place.into();
+    #[cfg(feature = "metrics")]
+    let input_len = place.len();
+
+    let result = do_unmix_with(place, mix_fn).and_then(validate);
+
+    #[cfg(feature = "metrics")]
+    metrics::emit(match &result {
+        Ok(padder) => metrics::Event::Decode { input_len, output_len: padder.payload_length() },
+        Err(_) => metrics::Event::DecodeFailure { input_len },
+    });
+    result
+}
+
+/// Decodes the input, returning the payload.
+///
+/// Any filler following the checksum, such as the one added by `encode_to_size`, is skipped, as the length field
+/// records the true length of the payload.
 pub fn decode(input: impl Into<Box<[u8]>>) -> Result<Box<[u8]>, PaddingValidationError> {
     do_decode(input.into()).map(|p| p.as_ref().into())
 }
 
+/// Decodes a borrowed input like `decode_ref`, returning a `Cow` for symmetry with `encode_cow`.
+///
+/// The payload is always owned, as it is only available after unmixing a copy of the input.
+pub fn decode_cow(input: &[u8]) -> Result<Cow<'static, [u8]>, PaddingValidationError> {
+    decode_ref(input).map(|payload| Cow::Owned(payload.into_vec()))
+}
+
+/// Decodes a borrowed input, copying it once for unmixing. This behaves exactly like `decode(input.to_vec())`.
+pub fn decode_ref(input: &[u8]) -> Result<Box<[u8]>, PaddingValidationError> {
+    decode(input)
+}
+
+/// Decodes the input and passes the payload to `parser`, without copying it out of the unmixed block.
+///
+/// This composes decoding with parsing the payload, e.g. as a further length-prefixed structure, without allocating
+/// for the payload.
+pub fn decode_and<T>(input: impl Into<Box<[u8]>>, parser: impl FnOnce(&[u8]) -> T) -> Result<T, PaddingValidationError> {
+    do_decode(input.into()).map(|padder| parser(padder.as_slice()))
+}
+
+/// Decodes the input into the validated `Padder`, without copying the payload out of it.
+///
+/// The payload is available via `Padder::as_slice`, and its position in the block via `Padder::payload_offset`.
+pub fn decode_to_padder(input: impl Into<Box<[u8]>>) -> Result<Padder, PaddingValidationError> {
+    do_decode(input.into())
+}
+
+/// Decodes the block at `range` in a larger buffer, copying only that region for unmixing.
+///
+/// Fails with `PaddingValidationError::NotAligned` before copying anything if the length of the region is not a
+/// multiple of `Padder::ALIGNMENT`.
+///
+/// # Panics
+///
+/// Panics if `range` is out of the bounds of `buf`, like slice indexing.
+pub fn decode_region(buf: &[u8], range: Range<usize>) -> Result<Box<[u8]>, PaddingValidationError> {
+    let region = &buf[range];
+    if !Mixer::is_aligned(region.len()) {
+        return Err(PaddingValidationError::NotAligned { length: region.len() });
+    }
+
+    decode_ref(region)
+}
+
+/// Decodes the block at `range` in a larger buffer like `decode_region`, but returns `None` instead of panicking if
+/// `range` is out of the bounds of `buf`.
+pub fn try_decode_region(buf: &[u8], range: Range<usize>) -> Option<Result<Box<[u8]>, PaddingValidationError>> {
+    buf.get(range.clone())?;
+    Some(decode_region(buf, range))
+}
+
+/// Decodes the input like `decode`, but fails with `PaddingValidationError::PayloadTooLarge` if the declared payload
+/// size exceeds `max_payload`.
+///
+/// An input longer than any block with a payload of at most `max_payload` bytes is rejected by its length alone,
+/// before unmixing, with `declared` being the smallest payload size a block of that length can declare. Otherwise the
+/// input is unmixed in place, and the declared size is checked before the checksum is calculated. Like `decode`, this
+/// copies a borrowed input into a new allocation first.
+pub fn decode_with_limit(input: impl Into<Box<[u8]>>, max_payload: usize) -> Result<Box<[u8]>, PaddingValidationError> {
+    let input = input.into();
+    if Padder::checked_padded_size(max_payload).is_some_and(|max_len| input.len() > max_len) {
+        #[cfg(feature = "metrics")]
+        metrics::emit(metrics::Event::DecodeFailure { input_len: input.len() });
+
+        let declared = Padder::remaining_capacity(input.len() - 1) + 1;
+        return Err(PaddingValidationError::PayloadTooLarge { declared, limit: max_payload });
+    }
+
+    do_decode_with(input, Mixer::mix, |mix| {
+        let (declared, _) = read_length_prefix(mix.as_slice())?;
+        if declared > max_payload {
+            return Err(PaddingValidationError::PayloadTooLarge { declared, limit: max_payload });
+        }
+
+        Padder::try_from_raw_with_filler(mix)
+    }).map(|p| p.as_ref().into())
+}
+
+/// Decodes the input like `decode`, but fails with `PaddingValidationError::UnexpectedPayloadLength` if the declared
+/// payload size is not `expected_len`.
+///
+/// Like `decode_with_limit`, the declared size is checked right after unmixing, before the checksum is calculated or
+/// the payload is copied.
+pub fn decode_expecting(input: impl Into<Box<[u8]>>, expected_len: usize) -> Result<Box<[u8]>, PaddingValidationError> {
+    do_decode_with(input, Mixer::mix, |mix| {
+        let (declared, _) = read_length_prefix(mix.as_slice())?;
+        if declared != expected_len {
+            return Err(PaddingValidationError::UnexpectedPayloadLength { expected: expected_len, actual: declared });
+        }
+
+        Padder::try_from_raw_with_filler(mix)
+    }).map(|p| p.as_ref().into())
+}
+
+/// Encodes the input with a 4-byte application tag prepended to the payload.
+///
+/// The output can be decoded by `decode_tagged`, which checks the tag.
+pub fn encode_tagged<T: AsRef<[u8]>>(input: T, tag: [u8; 4]) -> Box<[u8]> {
+    encode([&tag[..], input.as_ref()].concat())
+}
+
+/// Decodes the output of `encode_tagged`, failing with `PaddingValidationError::TagMismatch` if the tag is not
+/// `expected_tag`. The tag is stripped from the returned payload.
+///
+/// The `actual` tag of the error is `None` if the payload is shorter than a tag.
+pub fn decode_tagged(input: impl Into<Box<[u8]>>, expected_tag: [u8; 4]) -> Result<Box<[u8]>, PaddingValidationError> {
+    let padder = do_decode(input)?;
+    match padder.as_slice().strip_prefix(&expected_tag) {
+        Some(payload) => Ok(payload.into()),
+        None => Err(PaddingValidationError::TagMismatch {
+            expected: expected_tag,
+            actual: padder.as_slice().first_chunk().copied(),
+        }),
+    }
+}
+
+/// Encodes several records into a single block, each prefixed with its leb128-encoded length.
+///
+/// The records share one length field, checksum and mix, which saves space over encoding them separately. The output
+/// can be decoded by `decode_bundle`.
+///
+/// Like `encode` with empty input, an empty list of records gives empty output, which does not decode.
+pub fn encode_bundle(inputs: &[&[u8]]) -> Box<[u8]> {
+    let mut payload = Vec::new();
+    for input in inputs {
+        leb128::write::unsigned(&mut payload, input.len() as u64).unwrap();
+        payload.extend_from_slice(input);
+    }
+
+    encode(payload)
+}
+
+/// Decodes the output of `encode_bundle` into its records.
+///
+/// Fails with `PaddingValidationError::BadLengthField` if the block is valid but a record length is malformed or runs
+/// past the end of the payload.
+pub fn decode_bundle(input: impl Into<Box<[u8]>>) -> Result<Vec<Box<[u8]>>, PaddingValidationError> {
+    let padder = do_decode(input)?;
+    let mut rest = padder.as_slice();
+    let mut records = Vec::new();
+
+    while !rest.is_empty() {
+        let (length, leb128_size) = read_length_prefix(rest)?;
+        let record = rest[leb128_size..].get(..length).ok_or(PaddingValidationError::BadLengthField)?;
+        records.push(record.into());
+        rest = &rest[leb128_size + length..];
+    }
+
+    Ok(records)
+}
+
+/// Encodes each input into its own block, and concatenates the blocks, each prefixed with its length.
+///
+/// This is `encode_stream` for a slice of inputs; see there for the format. The output can be split and decoded by
+/// `decode_all`.
+pub fn encode_concat(inputs: &[Vec<u8>]) -> Box<[u8]> {
+    encode_stream(inputs)
+}
+
+/// Encodes each input into its own block, and concatenates the blocks, each prefixed with its length.
+///
+/// As the end of a mixed block cannot be told from its beginning, see `Mixer`, each block is preceded by its length in
+/// bytes as an unmixed leb128 integer, which is the only framing overhead. Empty inputs are kept as blocks of length 0, i.e. a single zero byte. The output can be split and decoded
+/// by `decode_stream` or `decode_all`, or read block by block by `stream::decode_one_block`.
+pub fn encode_stream<T: AsRef<[u8]>>(inputs: impl IntoIterator<Item = T>) -> Box<[u8]> {
+    let mut out = Vec::new();
+    for input in inputs {
+        stream::write_frame(&mut out, input.as_ref());
+    }
+
+    out.into_boxed_slice()
+}
+
+/// Splits the output of `encode_concat` or `encode_stream` into its blocks, and decodes each of them.
+///
+/// Fails with the first error `decode_stream` yields.
+pub fn decode_all(input: &[u8]) -> Result<Vec<Box<[u8]>>, PaddingValidationError> {
+    decode_stream(input).collect()
+}
+
+/// Splits the output of `encode_concat` or `encode_stream` into its blocks, and decodes each of them lazily, yielding
+/// the payloads in order.
+///
+/// Each block is found by reading its length prefix, so splitting takes time linear in the input length. If a length
+/// prefix cannot be parsed or runs past the end of the input, `PaddingValidationError::BadLengthField` is yielded,
+/// and the iterator ends. A block which fails to decode yields its error, and decoding goes on with the next block.
+///
+/// # Example
+///
+/// ```
+/// use gesist::{decode_stream, encode_stream};
+///
+/// let blob = encode_stream(["first", "second"]);
+/// let payloads: Vec<_> = decode_stream(&blob).collect::<Result<_, _>>().unwrap();
+/// assert_eq!(payloads, [&b"first"[..], b"second"].map(Box::from));
+/// ```
+pub fn decode_stream(input: &[u8]) -> impl Iterator<Item = Result<Box<[u8]>, PaddingValidationError>> + '_ {
+    let mut rest = input;
+    std::iter::from_fn(move || {
+        let block = match stream::read_frame_len(&mut rest) {
+            Ok(None) => return None,
+            Ok(Some(len)) if len <= rest.len() => {
+                let (block, tail) = rest.split_at(len);
+                rest = tail;
+                block
+            }
+            _ => {
+                rest = &[];
+                return Some(Err(PaddingValidationError::BadLengthField));
+            }
+        };
+
+        Some(stream::decode_frame_block(block))
+    })
+}
+
+/// Encodes the input like `encode`, but mixes it with `Mixer::mix_keyed`.
+///
+/// The output can only be decoded by `decode_keyed` with the same key. See `Mixer::mix_keyed` for the (limited)
+/// guarantees this gives.
+pub fn encode_keyed<T: AsRef<[u8]>>(input: T, key: u64) -> Box<[u8]> {
+    do_encode_with(input, |m| m.mix_keyed(key)).map_or_else(|| [].into(), |m| m.into())
+}
+
+/// Decodes the output of `encode_keyed` with the same key.
+pub fn decode_keyed(input: impl Into<Box<[u8]>>, key: u64) -> Result<Box<[u8]>, PaddingValidationError> {
+    do_decode_with(input, |m| m.mix_keyed(key), Padder::try_from_raw_with_filler).map(|p| p.as_ref().into())
+}
+
+/// Decodes the input and writes the payload to `dest`.
+///
+/// Prefer `decode_to_flat`, which returns a single error type that works with `?`.
 pub fn decode_to(input: impl Into<Box<[u8]>>, mut dest: impl Write) -> Result<io::Result<()>, PaddingValidationError> {
     do_decode(input.into()).map(|p| dest.write_all(p.as_slice()))
 }
 
+/// The error type of `decode_to_flat`, either a validation error or an IO error.
+pub enum DecodeToError {
+    Validation(PaddingValidationError),
+    Io(io::Error),
+}
+
+impl Debug for DecodeToError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeToError::Validation(e) => write!(f, "Validation error: {}", e),
+            DecodeToError::Io(e) => write!(f, "IO error: {}", e),
+        }
+    }
+}
+
+impl Display for DecodeToError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        <Self as Debug>::fmt(self, f)
+    }
+}
+
+impl Error for DecodeToError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DecodeToError::Validation(e) => Some(e),
+            DecodeToError::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<PaddingValidationError> for DecodeToError {
+    fn from(e: PaddingValidationError) -> Self {
+        DecodeToError::Validation(e)
+    }
+}
+
+impl From<io::Error> for DecodeToError {
+    fn from(e: io::Error) -> Self {
+        DecodeToError::Io(e)
+    }
+}
+
+/// Decodes the input and writes the payload to `dest`, like `decode_to`, but with a flattened error type.
+pub fn decode_to_flat(input: impl Into<Box<[u8]>>, mut dest: impl Write) -> Result<(), DecodeToError> {
+    let padder = do_decode(input.into())?;
+    dest.write_all(padder.as_slice())?;
+
+    Ok(())
+}
+
+/// Decodes the input and writes the payload to `dest` in pieces of `chunk` bytes, flushing `dest` after each piece.
+///
+/// The block is validated before anything is written, so `dest` receives nothing if the input is invalid. This bounds
+/// the size of each write, but the whole block is still held in memory while decoding.
+///
+/// # Panics
+///
+/// Panics if `chunk` is 0.
+pub fn decode_stream_to<W: Write>(input: impl Into<Box<[u8]>>, dest: W, chunk: usize) -> Result<(), DecodeToError> {
+    assert!(chunk > 0, "chunk size must be positive");
+    try_decode_stream_to(input, dest, chunk)
+}
+
+/// Decodes the input and writes the payload to `dest` in pieces like `decode_stream_to`, but fails with an
+/// `io::ErrorKind::InvalidInput` error instead of panicking if `chunk` is 0.
+pub fn try_decode_stream_to<W: Write>(input: impl Into<Box<[u8]>>, mut dest: W, chunk: usize) -> Result<(), DecodeToError> {
+    if chunk == 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "chunk size must be positive").into());
+    }
+
+    let padder = do_decode(input.into())?;
+    for piece in padder.payload_chunks(chunk) {
+        dest.write_all(piece)?;
+        dest.flush()?;
+    }
+
+    Ok(())
+}
+
 pub fn decode_from_base64(input: impl AsRef<[u8]>) -> Result<Result<Box<[u8]>, PaddingValidationError>, base64::DecodeError> {
     let bin = base64::prelude::BASE64_URL_SAFE.decode(input)?;
     Ok(decode(bin))
 }
 
+/// Decodes base64 like `decode_from_base64`, after removing all ASCII whitespace from the input, as the command line
+/// tool does.
+///
+/// The removed characters are space, horizontal tab, line feed, form feed and carriage return, i.e. those for which
+/// `u8::is_ascii_whitespace` holds.
+pub fn decode_from_base64_trimmed(input: impl AsRef<[u8]>) -> Result<Result<Box<[u8]>, PaddingValidationError>, base64::DecodeError> {
+    let stripped: Vec<u8> = input.as_ref().iter().copied().filter(|c| !c.is_ascii_whitespace()).collect();
+    decode_from_base64(stripped)
+}
+
+/// Decodes base64 like `decode_from_base64_trimmed`, after skipping a leading UTF-8 byte order mark (`EF BB BF`), as
+/// written by some editors.
+pub fn decode_from_base64_tolerant(input: impl AsRef<[u8]>) -> Result<Result<Box<[u8]>, PaddingValidationError>, base64::DecodeError> {
+    let input = input.as_ref();
+    decode_from_base64_trimmed(input.strip_prefix(b"\xef\xbb\xbf").unwrap_or(input))
+}
+
+pub fn decode_with_output(input: impl AsRef<[u8]>, output: Output) -> Result<Result<Box<[u8]>, PaddingValidationError>, TransportDecodeError> {
+    let bin = output.decode(input.as_ref())?;
+    Ok(decode(bin))
+}
+
+/// Encodes the input into base45 (RFC 9285), whose alphabet fits the alphanumeric mode of QR codes.
+pub fn encode_to_qr_alphanumeric<T: AsRef<[u8]>>(input: T) -> String {
+    String::from_utf8(encode_with_output(input, Output::Base45)).unwrap()
+}
+
+/// Decodes base45 as produced by `encode_to_qr_alphanumeric`.
+pub fn decode_from_qr_alphanumeric(input: impl AsRef<[u8]>) -> Result<Result<Box<[u8]>, PaddingValidationError>, TransportDecodeError> {
+    decode_with_output(input, Output::Base45)
+}
+
+/// Decodes hexadecimal in either case, after removing separators between the digits, e.g. `3d:a1:14` or `3d a1 14`.
+///
+/// The removed separators are ASCII whitespace (space, horizontal tab, line feed, form feed and carriage return),
+/// `:` and `-`. Any other non-hex-digit byte is an error, whose offset refers to the input with the separators
+/// removed.
+pub fn decode_from_hex(input: impl AsRef<[u8]>) -> Result<Result<Box<[u8]>, PaddingValidationError>, TransportDecodeError> {
+    let stripped: Vec<u8> = input.as_ref().iter().copied().filter(|&c| !c.is_ascii_whitespace() && c != b':' && c != b'-').collect();
+    decode_with_output(stripped, Output::Hex)
+}
+
+/// Checks whether the input looks like gesist-encoded data, e.g. for format sniffing.
+///
+/// The input must be aligned, and its length field must declare a payload whose padded size is at most the input
+/// length, the rest being filler. To read the length field, this unmixes a copy of the input, but it skips validating
+/// the checksum. Hence it can have false positives, and `decode` may still fail on inputs accepted here.
+pub fn looks_like_gesist(input: &[u8]) -> bool {
+    let Some(mut mix) = Mixer::new_with_copy(input) else {
+        return false;
+    };
+    mix.mix();
+
+    read_length_prefix(mix.as_slice())
+        .is_ok_and(|(payload_size, _)| Padder::checked_padded_size(payload_size).is_some_and(|padded_size| padded_size <= input.len()))
+}
+
+/// Decodes the input as far as possible, for forensic recovery.
+///
+/// The input is unmixed and the payload is returned according to its length field, clamped to the input, even if the
+/// validation fails. The validation error, if any, is returned alongside. If the length field cannot be parsed, the
+/// whole unmixed input is returned, and if the input cannot be unmixed at all, it is returned unchanged.
+pub fn decode_best_effort(input: impl Into<Box<[u8]>>) -> (Box<[u8]>, Option<PaddingValidationError>) {
+    let input = input.into();
+    let len = input.len();
+    if input.is_empty() || !Mixer::is_aligned(len) {
+        let error = Padder::validate(&input, true).err();
+        return (input, error);
+    }
+
+    let raw: Box<[u8]> = do_unmix(input).unwrap().into();
+    match read_length_prefix(&raw) {
+        Err(e) => (raw, Some(e)),
+        Ok((payload_size, leb128_size)) => {
+            let error = Padder::validate(&raw, true).err();
+            let payload_end = leb128_size.saturating_add(payload_size).min(len);
+            (raw[leb128_size.min(len)..payload_end].into(), error)
+        }
+    }
+}
+
+const BASE64_URL_SAFE_LOOSE: GeneralPurpose =
+    GeneralPurpose::new(&alphabet::URL_SAFE, GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent));
+const BASE64_STANDARD_LOOSE: GeneralPurpose =
+    GeneralPurpose::new(&alphabet::STANDARD, GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent));
+
+/// Decodes base64 input like `decode_from_base64`, but accepts it with or without padding, and in the standard
+/// alphabet as well as the URL-safe one.
+///
+/// As encoded data is always a multiple of 3 bytes long, the base64 produced by `encode_to_base64` is always a multiple
+/// of 4 characters long and never has padding. Padding, or the lack of it, only matters for input that was mangled or
+/// re-encoded by other tools, which this accepts either way. The URL-safe alphabet is tried first.
+pub fn decode_from_base64_loose(input: impl AsRef<[u8]>) -> Result<Result<Box<[u8]>, PaddingValidationError>, base64::DecodeError> {
+    let input = input.as_ref();
+    let bin = BASE64_URL_SAFE_LOOSE.decode(input).or_else(|e| match e {
+        base64::DecodeError::InvalidByte(_, _) => BASE64_STANDARD_LOOSE.decode(input),
+        e => Err(e),
+    })?;
+
+    Ok(decode(bin))
+}
+
 pub struct InPlaceDecodeResult {
     pub content: Box<[u8]>,
     pub offset: usize,
@@ -73,13 +807,569 @@ pub fn decode_in_place(input: Box<[u8]>) -> Result<InPlaceDecodeResult, PaddingV
     })
 }
 
+impl InPlaceDecodeResult {
+    /// Returns a slice of the payload in the decoded buffer.
+    pub fn as_payload(&self) -> &[u8] {
+        &self.content[self.offset..self.offset + self.length]
+    }
+}
+
+/// Implementation of the `Deref` trait for the `InPlaceDecodeResult` struct.
+///
+/// This allows for the `InPlaceDecodeResult` to be used as a byte slice of the payload.
+impl Deref for InPlaceDecodeResult {
+    type Target = [u8];
+
+    /// Returns a slice of the payload.
+    fn deref(&self) -> &Self::Target {
+        self.as_payload()
+    }
+}
+
+/// Implementation of the `AsRef` trait for the `InPlaceDecodeResult` struct.
+impl AsRef<[u8]> for InPlaceDecodeResult {
+    /// Returns a slice of the payload.
+    fn as_ref(&self) -> &[u8] {
+        self.as_payload()
+    }
+}
+
+/// Implementation of the `Borrow` trait for the `InPlaceDecodeResult` struct.
+///
+/// It's safe to implement `Borrow` because `InPlaceDecodeResult` implements none of `Eq`, `Ord` and `Hash`.
+impl Borrow<[u8]> for InPlaceDecodeResult {
+    /// Returns a slice of the payload.
+    fn borrow(&self) -> &[u8] {
+        self.as_payload()
+    }
+}
+
+/// Implementation of the `From` trait for converting an `InPlaceDecodeResult` into a boxed slice of the payload.
+impl From<InPlaceDecodeResult> for Box<[u8]> {
+    /// Copies the payload into a new boxed slice.
+    fn from(result: InPlaceDecodeResult) -> Self {
+        result.deref().into()
+    }
+}
+
+/// Decodes the input, reusing its allocation for the output.
+///
+/// After unmixing in place, the payload is moved to the front of the buffer and the buffer is truncated. This costs a
+/// `memmove` of the payload, which is at most a couple of bytes shorter than the input, but avoids allocating and
+/// filling a second buffer as `decode` does. Shrinking the allocation is up to the allocator, and usually happens in
+/// place.
+pub fn decode_shrink(input: Box<[u8]>) -> Result<Box<[u8]>, PaddingValidationError> {
+    decode_in_place(input).map(|r| {
+        let mut content = r.content.into_vec();
+        content.copy_within(r.offset..r.offset + r.length, 0);
+        content.truncate(r.length);
+        content.into_boxed_slice()
+    })
+}
+
+/// Encodes the input into a `Bytes`, taking over the allocation of the encoded data without copying it.
+#[cfg(feature = "bytes")]
+pub fn encode_bytes(input: &[u8]) -> bytes::Bytes {
+    bytes::Bytes::from(encode(input))
+}
+
+/// Decodes a `Bytes` in place, returning a `Bytes` which shares the allocation of the unmixed block and covers just
+/// the payload.
+///
+/// The input is only copied if its allocation is shared with other `Bytes`, or does not exactly fit the input.
+#[cfg(feature = "bytes")]
+pub fn decode_bytes(input: bytes::Bytes) -> Result<bytes::Bytes, PaddingValidationError> {
+    let result = decode_in_place(Vec::from(input).into_boxed_slice())?;
+    Ok(bytes::Bytes::from(result.content).slice(result.offset..result.offset + result.length))
+}
+
+/// Encodes and decodes a few known vectors, checking the outputs against the fixed format and the round trip.
+///
+/// This is a cheap sanity check for the build, e.g. at startup. Returns an error describing the first failed check.
+pub fn self_test() -> Result<(), &'static str> {
+    const VECTORS: [(&[u8], &str); 2] = [(b"a", "PaEU"), (b"b", "pJxd")];
+
+    for (payload, expected) in VECTORS {
+        if encode_to_base64(payload) != expected {
+            return Err("encoding a known vector gives an unexpected output");
+        }
+
+        if decode_from_base64(expected).ok().and_then(Result::ok).as_deref() != Some(payload) {
+            return Err("decoding a known vector gives an unexpected payload");
+        }
+    }
+
+    for len in [1, 2, 127, 128, 1000] {
+        let payload: Vec<u8> = (0..len).map(|i| i as u8).collect();
+        let encoded = encode(&payload);
+        if encoded.len() != padded_size(len) {
+            return Err("encoded length does not match padded_size");
+        }
+
+        if decode(encoded).as_deref() != Ok(payload.as_slice()) {
+            return Err("round trip does not recover the payload");
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::encode_to_base64;
+    use base64::Engine;
+    use crate::{decode, decode_best_effort, decode_expecting, decode_from_base64_loose, decode_in_place, decode_keyed, decode_tagged, encode_tagged, decode_with_limit, encode_keyed, encode_padder, decode_shrink, decode_with_output, encode, encode_batch, encode_simd_aligned, encode_to_base64, encode_with_output, looks_like_gesist, SIMD_ALIGNMENT};
+    use crate::mixer::Mixer;
+    use crate::padder::{Padder, PaddingValidationError};
+    use crate::transport::Output;
 
     #[test]
     fn basic_base64_encode() {
         assert_eq!("PaEU", encode_to_base64(b"a"));
         assert_eq!("pJxd", encode_to_base64(b"b"));
     }
+
+    #[test]
+    fn batch_encode_matches_encode() {
+        let inputs = [&b"a"[..], b"", b"hello, world"];
+        let encoded = encode_batch(&inputs);
+        assert_eq!(encoded.len(), inputs.len());
+        for (input, encoded) in inputs.iter().zip(encoded) {
+            assert_eq!(encode(input), encoded);
+        }
+    }
+
+    #[test]
+    fn shrink_decode_matches_decode() {
+        let input = [0x5a; 300];
+        assert_eq!(decode(encode(input)).unwrap(), decode_shrink(encode(input)).unwrap());
+    }
+
+    #[test]
+    fn sniff_encoded_data() {
+        assert!(looks_like_gesist(&encode(b"hello, world")));
+        assert!(!looks_like_gesist(b"hello, world!"));
+        assert!(!looks_like_gesist(&[0xff; 12]));
+        assert!(!looks_like_gesist(&[]));
+    }
+
+    #[test]
+    fn in_place_result_as_payload() {
+        let result = decode_in_place(encode(b"hello, world")).unwrap();
+        assert_eq!(&*result, b"hello, world");
+        assert_eq!(result.as_payload(), b"hello, world");
+        assert_eq!(AsRef::<[u8]>::as_ref(&result), b"hello, world");
+        assert_eq!(std::borrow::Borrow::<[u8]>::borrow(&result), b"hello, world");
+        assert_eq!(Box::<[u8]>::from(result).as_ref(), b"hello, world");
+    }
+
+    #[test]
+    fn keyed_round_trip() {
+        let input = b"hello, world";
+        for key in [0, 1, 0xdead_beef, u64::MAX] {
+            assert_eq!(input, decode_keyed(encode_keyed(input, key), key).unwrap().as_ref());
+        }
+
+        assert_ne!(encode_keyed(input, 1), encode_keyed(input, 2));
+        assert!(decode_keyed(encode_keyed(input, 1), 2).is_err());
+    }
+
+    #[test]
+    fn best_effort_decode() {
+        assert_eq!(decode_best_effort(encode(b"hello, world")), (b"hello, world"[..].into(), None));
+
+        let mut corrupted = encode(b"hello, world");
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0x01;
+        let (payload, error) = decode_best_effort(corrupted);
+        assert!(error.is_some());
+        assert!(!payload.is_empty());
+
+        assert_eq!(decode_best_effort(&b"ab"[..]), (b"ab"[..].into(), Some(PaddingValidationError::NotAligned { length: 2 })));
+    }
+
+    #[test]
+    fn limited_decode() {
+        assert_eq!(decode_with_limit(encode([0; 100]), 100).unwrap().as_ref(), [0; 100]);
+        assert_eq!(decode_with_limit(encode([0; 100]), 99), Err(PaddingValidationError::PayloadTooLarge { declared: 100, limit: 99 }));
+        assert_eq!(decode_with_limit(encode([0; 100]), 16), Err(PaddingValidationError::PayloadTooLarge { declared: 98, limit: 16 }));
+        assert_eq!(decode_with_limit(vec![0; 3_000_000], 16), Err(PaddingValidationError::PayloadTooLarge { declared: 2_999_993, limit: 16 }));
+    }
+
+    #[test]
+    fn empty_input() {
+        assert!(Mixer::new([]).is_none());
+        assert!(encode([]).is_empty());
+        assert_eq!(decode([]), Err(PaddingValidationError::BadLengthField));
+    }
+
+    #[test]
+    fn tagged_round_trip() {
+        assert_eq!(decode_tagged(encode_tagged(b"hello", *b"TEXT"), *b"TEXT").unwrap().as_ref(), b"hello");
+        assert_eq!(decode_tagged(encode_tagged(b"", *b"TEXT"), *b"TEXT").unwrap().as_ref(), b"");
+        assert_eq!(
+            decode_tagged(encode_tagged(b"hello", *b"TEXT"), *b"JSON"),
+            Err(PaddingValidationError::TagMismatch { expected: *b"JSON", actual: Some(*b"TEXT") }),
+        );
+        assert_eq!(decode_tagged(encode(b"hi"), *b"TEXT"), Err(PaddingValidationError::TagMismatch { expected: *b"TEXT", actual: None }));
+    }
+
+    #[test]
+    fn simd_aligned_round_trip() {
+        for len in [1, 12, 45, 46, 100] {
+            let input = vec![0x5a; len];
+            let encoded = encode_simd_aligned(&input);
+            assert_eq!(encoded.len() % SIMD_ALIGNMENT, 0);
+            assert_eq!(decode(encoded).unwrap().as_ref(), input);
+        }
+    }
+
+    #[test]
+    fn loose_base64_decode() {
+        let mut alphabet_differs = false;
+        for len in 1..64 {
+            let input = vec![len as u8; len];
+            let standard = base64::prelude::BASE64_STANDARD.encode(encode(&input));
+            alphabet_differs |= standard.contains(['+', '/']);
+            assert_eq!(decode_from_base64_loose(&standard).unwrap().unwrap().as_ref(), input);
+        }
+        assert!(alphabet_differs);
+        assert_eq!(decode_from_base64_loose("PaEU").unwrap().unwrap().as_ref(), b"a");
+        assert!(decode_from_base64_loose("PaEU====").is_err());
+    }
+
+    #[test]
+    fn output_round_trip() {
+        assert_eq!(b"PaEU", encode_with_output(b"a", Output::Base64UrlSafe).as_slice());
+        assert_eq!(b"3da114", encode_with_output(b"a", Output::Hex).as_slice());
+        assert_eq!(b"3DA114", encode_with_output(b"a", Output::HexUpper).as_slice());
+        assert_eq!(b"a", decode_with_output(b"3DA114", Output::Hex).unwrap().unwrap().as_ref());
+
+        for output in [Output::Raw, Output::Base64UrlSafe, Output::Base64Standard, Output::Hex, Output::HexUpper, Output::Base45] {
+            let encoded = encode_with_output(b"hello, world", output);
+            assert_eq!(b"hello, world", decode_with_output(encoded, output).unwrap().unwrap().as_ref());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn encode_to_size_round_trip() {
+        use crate::encode_to_size;
+
+        let encoded = encode_to_size(b"hello", 30).unwrap();
+        assert_eq!(encoded.len(), 30);
+        assert!(looks_like_gesist(&encoded));
+        assert_eq!(b"hello", decode(encoded).unwrap().as_ref());
+
+        assert!(encode_to_size(b"hello", 29).is_none());
+        assert!(encode_to_size(b"hello", 6).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn salted_round_trip() {
+        use crate::{decode_from_base64, encode_salted, padded_size, to_base64, SALT_SIZE};
+
+        let first = encode_salted(b"hello");
+        let second = encode_salted(b"hello");
+        assert_eq!(first.len(), padded_size(5) + SALT_SIZE);
+        assert_ne!(first, second);
+        assert_eq!(b"hello", decode(first).unwrap().as_ref());
+        assert_eq!(b"hello", decode_from_base64(to_base64(&second)).unwrap().unwrap().as_ref());
+        assert_eq!(b"", decode(encode_salted(b"")).unwrap().as_ref());
+    }
+
+    #[test]
+    fn padded_sizes_table() {
+        use crate::{padded_size, PADDED_SIZES};
+
+        for (n, &size) in PADDED_SIZES.iter().enumerate() {
+            assert_eq!(size, padded_size(n));
+        }
+
+        let block = [0u8; PADDED_SIZES[32]];
+        assert_eq!(block.len(), encode([0u8; 32]).len());
+    }
+
+    #[test]
+    fn chunked_decode_to_writer() {
+        use crate::decode_stream_to;
+
+        let payload: Vec<u8> = (0..=255).collect();
+        let mut dest = Vec::new();
+        decode_stream_to(encode(&payload), &mut dest, 100).unwrap();
+        assert_eq!(payload, dest);
+
+        let mut encoded = encode(&payload);
+        encoded[0] ^= 1;
+        let mut dest = Vec::new();
+        assert!(decode_stream_to(encoded, &mut dest, 100).is_err());
+        assert!(dest.is_empty());
+    }
+
+    #[test]
+    fn self_test_passes() {
+        assert_eq!(crate::self_test(), Ok(()));
+    }
+
+    #[test]
+    fn trimmed_base64_decode() {
+        use crate::{decode_from_base64, decode_from_base64_trimmed};
+
+        let encoded = encode_to_base64(b"hello, world");
+        let formatted = format!(" {}\r\n{}\t\n", &encoded[..8], &encoded[8..]);
+        assert!(decode_from_base64(&formatted).is_err());
+        assert_eq!(b"hello, world", decode_from_base64_trimmed(&formatted).unwrap().unwrap().as_ref());
+    }
+
+    #[test]
+    fn decode_regions_of_buffer() {
+        use crate::decode_region;
+
+        let first = encode(b"hello");
+        let second = encode(b"world!");
+        let buf = [first.as_ref(), second.as_ref()].concat();
+
+        assert_eq!(b"hello", decode_region(&buf, 0..first.len()).unwrap().as_ref());
+        assert_eq!(b"world!", decode_region(&buf, first.len()..buf.len()).unwrap().as_ref());
+        assert_eq!(decode_region(&buf, 1..first.len()), Err(PaddingValidationError::NotAligned { length: first.len() - 1 }));
+    }
+
+    #[test]
+    fn encode_prepared_padder() {
+        use crate::encode_padder_ref;
+
+        let mut padder = Padder::new(b"hello");
+        assert_eq!(encode_padder_ref(&padder), encode(b"hello"));
+
+        padder.as_mut().copy_from_slice(b"jelly");
+        assert_eq!(encode_padder_ref(&padder), encode(b"jelly"));
+        assert_eq!(encode_padder(padder), encode(b"jelly"));
+    }
+
+    #[test]
+    fn base64_building_blocks() {
+        use crate::{from_base64, to_base64};
+
+        let encoded = encode(b"hello");
+        assert_eq!(to_base64(&encoded), encode_to_base64(b"hello"));
+        assert_eq!(from_base64(&to_base64(&encoded)).unwrap(), encoded);
+        assert!(from_base64("PaE").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "bytes")]
+    fn bytes_round_trip() {
+        use crate::{decode_bytes, encode_bytes};
+
+        let encoded = encode_bytes(b"hello, world");
+        assert_eq!(encoded.as_ref(), encode(b"hello, world").as_ref());
+        assert_eq!(decode_bytes(encoded.clone()).unwrap().as_ref(), b"hello, world");
+        assert!(decode_bytes(encoded.slice(3..)).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn min_size_round_trip() {
+        use crate::{encode_min_size, padded_size};
+
+        let encoded = encode_min_size(b"hi", 64);
+        assert_eq!(encoded.len(), 66);
+        assert_eq!(b"hi", decode(encoded).unwrap().as_ref());
+
+        let payload = [7u8; 100];
+        let encoded = encode_min_size(payload, 64);
+        assert_eq!(encoded.len(), padded_size(100));
+        assert_eq!(payload, decode(encoded).unwrap().as_ref());
+    }
+
+    #[test]
+    fn decode_into_padder() {
+        use crate::decode_to_padder;
+
+        let padder = decode_to_padder(encode(b"hello")).unwrap();
+        assert_eq!(padder.as_slice(), b"hello");
+        assert_eq!(padder.payload_offset(), 1);
+        assert!(decode_to_padder(encode(b"hello")[3..].to_vec()).is_err());
+    }
+
+    #[test]
+    fn expansion_ratios() {
+        use crate::{base64_expansion_ratio, expansion_ratio};
+
+        assert_eq!(expansion_ratio(1), 3.0);
+        assert_eq!(expansion_ratio(4), 1.5);
+        assert_eq!(expansion_ratio(300), 1.01);
+        assert_eq!(base64_expansion_ratio(1), 4.0);
+        assert_eq!(base64_expansion_ratio(5), encode_to_base64([0; 5]).len() as f64 / 5.0);
+        assert!(expansion_ratio(0).is_infinite());
+        assert_eq!(expansion_ratio(usize::MAX), 1.0);
+        assert_eq!(crate::checked_padded_size(usize::MAX), None);
+    }
+
+    #[test]
+    fn bundle_round_trip() {
+        use crate::{decode_bundle, encode_bundle};
+
+        let records: [&[u8]; 4] = [b"hello", b"", &[0x5a; 200], b"world"];
+        let decoded = decode_bundle(encode_bundle(&records)).unwrap();
+        assert_eq!(decoded.iter().map(AsRef::as_ref).collect::<Vec<_>>(), records);
+
+        assert_eq!(decode_bundle(encode([5, b'a'])), Err(PaddingValidationError::BadLengthField));
+    }
+
+    #[test]
+    fn hex_with_separators() {
+        use crate::decode_from_hex;
+        use crate::transport::TransportDecodeError;
+
+        assert_eq!(b"a", decode_from_hex("3d:a1:14").unwrap().unwrap().as_ref());
+        assert_eq!(b"a", decode_from_hex("3D A1\n14").unwrap().unwrap().as_ref());
+        assert_eq!(b"a", decode_from_hex("3d-a1-14").unwrap().unwrap().as_ref());
+        assert_eq!(decode_from_hex("3d.a1.14"), Err(TransportDecodeError::InvalidHexCharacter { offset: 2, character: b'.' }));
+    }
+
+    #[test]
+    fn panic_free_variants() {
+        use crate::{try_decode_region, try_decode_stream_to, DecodeToError};
+
+        let encoded = encode(b"hello");
+        assert_eq!(try_decode_region(&encoded, 0..encoded.len()).unwrap().unwrap().as_ref(), b"hello");
+        assert!(try_decode_region(&encoded, 0..encoded.len() + 3).is_none());
+
+        assert!(matches!(try_decode_stream_to(encoded, Vec::new(), 0), Err(DecodeToError::Io(_))));
+    }
+
+    #[test]
+    fn qr_alphanumeric_round_trip() {
+        use crate::{decode_from_qr_alphanumeric, encode_to_qr_alphanumeric};
+
+        let encoded = encode_to_qr_alphanumeric(b"hello, world");
+        assert_eq!(b"hello, world", decode_from_qr_alphanumeric(encoded).unwrap().unwrap().as_ref());
+    }
+
+    #[test]
+    fn tolerant_base64_decode() {
+        use crate::decode_from_base64_tolerant;
+
+        let saved = format!("\u{feff}{}\r\n", encode_to_base64(b"hello"));
+        assert_eq!(b"hello", decode_from_base64_tolerant(&saved).unwrap().unwrap().as_ref());
+        assert_eq!(b"hello", decode_from_base64_tolerant(encode_to_base64(b"hello")).unwrap().unwrap().as_ref());
+    }
+
+    #[test]
+    fn cow_round_trip() {
+        use std::borrow::Cow;
+        use crate::{decode_cow, encode_cow};
+
+        assert!(matches!(encode_cow(b""), Cow::Borrowed(b) if b.is_empty()));
+        let encoded = encode_cow(b"hello");
+        assert!(matches!(encoded, Cow::Owned(_)));
+        assert_eq!(encoded.as_ref(), encode(b"hello").as_ref());
+        assert_eq!(decode_cow(&encoded).unwrap().as_ref(), b"hello");
+    }
+
+    #[test]
+    fn concatenated_blocks() {
+        use crate::{decode_all, encode_concat};
+
+        let inputs = vec![b"hello".to_vec(), vec![0x5a; 300], vec![], b"world".to_vec()];
+        let concat = encode_concat(&inputs);
+        assert_eq!(&concat[..10], [&[9][..], &encode(b"hello")].concat());
+        assert_eq!(&concat[10..], encode_concat(&inputs[1..]).as_ref());
+
+        let decoded = decode_all(&concat).unwrap();
+        assert_eq!(decoded, [&b"hello"[..], &[0x5a; 300], b"", b"world"].map(Box::<[u8]>::from));
+
+        assert_eq!(decode_all(&concat[..concat.len() - 3]), Err(PaddingValidationError::BadLengthField));
+        assert_eq!(decode_all(&concat[..concat.len() - 1]), Err(PaddingValidationError::BadLengthField));
+    }
+
+    #[test]
+    fn concatenated_random_blocks() {
+        use rand::{Rng, SeedableRng};
+        use rand::rngs::StdRng;
+        use crate::{decode_all, encode_concat};
+
+        let mut rng = StdRng::seed_from_u64(679);
+        let inputs: Vec<Vec<u8>> = (0..5000).map(|_| {
+            let len = rng.gen_range(0..64);
+            (0..len).map(|_| rng.gen()).collect()
+        }).collect();
+
+        let decoded = decode_all(&encode_concat(&inputs)).unwrap();
+        assert_eq!(decoded.len(), inputs.len());
+        assert!(decoded.iter().zip(&inputs).all(|(decoded, input)| decoded.as_ref() == input.as_slice()));
+    }
+
+    #[test]
+    fn decode_and_parse() {
+        use crate::decode_and;
+
+        let payload = [&4u32.to_be_bytes()[..], b"gesist"].concat();
+        let parse = |p: &[u8]| {
+            let len = u32::from_be_bytes(p[..4].try_into().unwrap()) as usize;
+            p[4..4 + len].to_vec()
+        };
+        assert_eq!(decode_and(encode(&payload), parse).unwrap(), b"gesi");
+        assert!(decode_and(encode(&payload)[3..].to_vec(), parse).is_err());
+    }
+
+    #[test]
+    fn expecting_decode_checks_payload_length() {
+        assert_eq!(decode_expecting(encode(b"hello"), 5).unwrap().as_ref(), b"hello");
+        assert_eq!(
+            decode_expecting(encode(b"hello"), 4),
+            Err(PaddingValidationError::UnexpectedPayloadLength { expected: 4, actual: 5 }),
+        );
+    }
+
+    #[test]
+    fn tiny_encode_matches_mixer() {
+        for len in 1..=20 {
+            let input: Vec<u8> = (0..len as u8).map(|b| b.wrapping_mul(37)).collect();
+            let expected = encode_padder(Padder::new(&input));
+            assert_eq!(encode(&input), expected);
+            assert_eq!(crate::encode_cow(&input).as_ref(), expected.as_ref());
+            assert_eq!(encode_to_base64(&input), crate::to_base64(&expected));
+            assert_eq!(decode(encode(&input)).unwrap().as_ref(), input.as_slice());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn seeded_rng_gives_reproducible_salt() {
+        use rand::SeedableRng;
+        use rand::rngs::StdRng;
+        use crate::encode_salted_with_rng;
+
+        let a = encode_salted_with_rng(b"hello", &mut StdRng::seed_from_u64(42));
+        let b = encode_salted_with_rng(b"hello", &mut StdRng::seed_from_u64(42));
+        let c = encode_salted_with_rng(b"hello", &mut StdRng::seed_from_u64(43));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(decode(a).unwrap().as_ref(), b"hello");
+    }
+
+    #[test]
+    fn stream_decode_reports_trailing_bytes() {
+        use crate::{decode_stream, encode_stream};
+
+        let mut blob = encode_stream([&b"hello"[..], b"", &[0x5a; 200]]).into_vec();
+        let payloads: Vec<_> = decode_stream(&blob).collect();
+        assert_eq!(payloads.len(), 3);
+        assert!(payloads[1].as_deref().unwrap().is_empty());
+        assert_eq!(payloads[2].as_deref().unwrap(), [0x5a; 200]);
+
+        blob.extend_from_slice(&[1, 2]);
+        let mut stream = decode_stream(&blob);
+        assert_eq!(stream.next().unwrap().unwrap().as_ref(), b"hello");
+        assert!(stream.next().unwrap().is_ok());
+        assert!(stream.next().unwrap().is_ok());
+        assert_eq!(stream.next(), Some(Err(PaddingValidationError::NotAligned { length: 1 })));
+        assert_eq!(stream.next(), None);
+        drop(stream);
+
+        blob.push(0x80);
+        assert_eq!(decode_stream(&blob).last(), Some(Err(PaddingValidationError::BadLengthField)));
+    }
 }
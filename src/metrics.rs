@@ -0,0 +1,70 @@
+use std::sync::RwLock;
+
+/// `Event` describes an encode or decode operation, as reported to the metrics hook.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Event {
+    /// An input of `input_len` bytes was encoded into `output_len` bytes.
+    Encode { input_len: usize, output_len: usize },
+    /// An input of `input_len` bytes was decoded into a payload of `output_len` bytes.
+    Decode { input_len: usize, output_len: usize },
+    /// An input of `input_len` bytes failed to decode.
+    DecodeFailure { input_len: usize },
+}
+
+type Hook = Box<dyn Fn(Event) + Send + Sync>;
+
+static HOOK: RwLock<Option<Hook>> = RwLock::new(None);
+
+/// Registers a hook to be called on every encode and decode operation, replacing the previous one.
+///
+/// Every encode and decode function at the crate root reports one event per block, as do the functions built on them,
+/// e.g. the transports, `encode_stream`, `decode_stream` and the `stream` module, and the `ffi` and `wasm` bindings.
+/// Encoding empty input reports an `Event::Encode` with both lengths 0, while an empty block in a stream, which is
+/// not decoded, reports nothing. The forensic `decode_best_effort` and the `looks_like_gesist` check report nothing,
+/// and neither do the lower-level `Padder` and `Mixer` APIs, such as `Padder::try_from_raw`, `Padder::reencode` and
+/// `Mixer::mix`.
+///
+/// The hook is called synchronously on the thread performing the operation, so it should be cheap.
+pub fn set_metrics_hook(hook: Box<dyn Fn(Event) + Send + Sync>) {
+    *HOOK.write().unwrap_or_else(|e| e.into_inner()) = Some(hook);
+}
+
+/// Removes the registered hook, if any.
+pub fn clear_metrics_hook() {
+    *HOOK.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Reports an event to the registered hook, doing nothing if there is none.
+pub(crate) fn emit(event: Event) {
+    if let Some(hook) = HOOK.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        hook(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use crate::{decode, encode, encode_padder, Padder};
+    use crate::metrics::{clear_metrics_hook, set_metrics_hook, Event};
+
+    #[test]
+    fn metrics_hook_reports_events() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorder = events.clone();
+        set_metrics_hook(Box::new(move |e| recorder.lock().unwrap().push(e)));
+
+        let encoded = encode([0; 777]);
+        decode(encoded.clone()).unwrap();
+        decode(&encoded[3..]).unwrap_err();
+        let padded = encode_padder(Padder::new([1; 555]));
+        decode(padded.clone()).unwrap();
+        clear_metrics_hook();
+
+        let events = events.lock().unwrap();
+        assert!(events.contains(&Event::Encode { input_len: 777, output_len: encoded.len() }));
+        assert!(events.contains(&Event::Decode { input_len: encoded.len(), output_len: 777 }));
+        assert!(events.contains(&Event::DecodeFailure { input_len: encoded.len() - 3 }));
+        assert!(events.contains(&Event::Encode { input_len: 555, output_len: padded.len() }));
+        assert!(events.contains(&Event::Decode { input_len: padded.len(), output_len: 555 }));
+    }
+}
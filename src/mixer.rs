@@ -1,4 +1,7 @@
 use std::borrow::Borrow;
+use std::ops::Range;
+#[cfg(feature = "profile")]
+use std::time::{Duration, Instant};
 
 use super::padder::Padder;
 
@@ -31,16 +34,16 @@ macro_rules! mix_rule {
         mix_rule_inner!($content,0..($content.len()-1),$var_index,$var_this,$var_next,1,$body)
     };
     ($content:expr,u2d,$width:expr,$var_index:ident,$var_this:ident,$var_prev:ident,$body:block) => {
-        mix_rule_inner!(guarded,$width,$content,$width..($content.len()),$var_index,$var_this,$var_prev,-$width,$body)
+        mix_rule_inner!(guarded,$width,$content,$width..($content.len()),$var_index,$var_this,$var_prev,-($width as isize),$body)
     };
     ($content:expr,u2dr,$width:expr,$var_index:ident,$var_this:ident,$var_prev:ident,$body:block) => {
-        mix_rule_inner!(guarded,$width,$content,($width..($content.len())).rev(),$var_index,$var_this,$var_prev,-$width,$body)
+        mix_rule_inner!(guarded,$width,$content,($width..($content.len())).rev(),$var_index,$var_this,$var_prev,-($width as isize),$body)
     };
     ($content:expr,d2u,$width:expr,$var_index:ident,$var_this:ident,$var_next:ident,$body:block) => {
-        mix_rule_inner!(guarded,$width,$content,(0..($content.len()-$width)).rev(),$var_index,$var_this,$var_next,$width,$body)
+        mix_rule_inner!(guarded,$width,$content,(0..($content.len()-$width)).rev(),$var_index,$var_this,$var_next,$width as isize,$body)
     };
     ($content:expr,d2ur,$width:expr,$var_index:ident,$var_this:ident,$var_next:ident,$body:block) => {
-        mix_rule_inner!(guarded,$width,$content,0..($content.len()-$width),$var_index,$var_this,$var_next,$width,$body)
+        mix_rule_inner!(guarded,$width,$content,0..($content.len()-$width),$var_index,$var_this,$var_next,$width as isize,$body)
     };
     ($content:expr,byte,$var_index:ident,$var_this:ident,$body:block) => {
         for $var_index in 0..($content.len()) {
@@ -56,12 +59,165 @@ macro_rules! mix_rule {
     };
 }
 
+/// The `MixProfile` struct holds the time spent in each step of a `Mixer::mix_profiled` call.
+#[cfg(feature = "profile")]
+#[derive(Clone, Debug)]
+pub struct MixProfile {
+    /// The name and the duration of each step, in the order they are applied.
+    pub steps: Vec<(&'static str, Duration)>,
+}
+
+#[cfg(feature = "profile")]
+impl MixProfile {
+    /// Returns the total time spent in all steps.
+    pub fn total(&self) -> Duration {
+        self.steps.iter().map(|(_, d)| *d).sum()
+    }
+}
+
+/// The number of bits each 3-byte block is rotated by, i.e. `(row * 2 + 1) % 8` for the 0-based block index `row`,
+/// which repeats every 4 blocks.
+const ROTATION_SHIFTS: [usize; 4] = [1, 3, 5, 7];
+
+/// `MixStep` names each of the steps `Mixer::mix` applies, in the order they are applied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MixStep {
+    /// Step 1: head-to-tail prefix sum.
+    HeadTailSum,
+    /// Step 2: up-to-down prefix xor of width `Mixer::MIX_WIDTH_A`.
+    UpDownXor,
+    /// Step 3: add index to each byte.
+    AddIndex,
+    /// Shift 1: rotate each 3-byte block left.
+    RotateLeft,
+    /// Step 4: down-to-up prefix sum of width `Mixer::MIX_WIDTH_B`.
+    DownUpSum,
+    /// Step 5: tail-to-head prefix xor.
+    TailHeadXor,
+    /// Shift 2: middle shift of each 3-byte block.
+    MiddleShift,
+    /// Step 5r: tail-to-head inverse prefix xor.
+    TailHeadXorInverse,
+    /// Step 4r: down-to-up inverse prefix sum.
+    DownUpSumInverse,
+    /// Shift 1r: rotate each 3-byte block right.
+    RotateRight,
+    /// Step 3r: subtract index from each byte.
+    SubtractIndex,
+    /// Step 2r: up-to-down inverse prefix xor.
+    UpDownXorInverse,
+    /// Step 1r: head-to-tail inverse prefix sum.
+    HeadTailSumInverse,
+}
+
+impl MixStep {
+    /// The steps `Mixer::mix` applies, in order. The steps after the middle shift undo the ones before it in reverse
+    /// order, which makes `mix` its own inverse.
+    ///
+    /// `mix` itself applies the steps in straight-line code, while `Mixer::mix_profiled`, `Mixer::mix_keyed` and
+    /// `Mixer::mix_without_index_step` are driven by this table.
+    pub const SCHEDULE: [MixStep; 13] = [
+        MixStep::HeadTailSum,
+        MixStep::UpDownXor,
+        MixStep::AddIndex,
+        MixStep::RotateLeft,
+        MixStep::DownUpSum,
+        MixStep::TailHeadXor,
+        MixStep::MiddleShift,
+        MixStep::TailHeadXorInverse,
+        MixStep::DownUpSumInverse,
+        MixStep::RotateRight,
+        MixStep::SubtractIndex,
+        MixStep::UpDownXorInverse,
+        MixStep::HeadTailSumInverse,
+    ];
+
+    /// Returns the step undoing this one. The middle shift is its own inverse.
+    const fn inverse(self) -> MixStep {
+        match self {
+            MixStep::HeadTailSum => MixStep::HeadTailSumInverse,
+            MixStep::UpDownXor => MixStep::UpDownXorInverse,
+            MixStep::AddIndex => MixStep::SubtractIndex,
+            MixStep::RotateLeft => MixStep::RotateRight,
+            MixStep::DownUpSum => MixStep::DownUpSumInverse,
+            MixStep::TailHeadXor => MixStep::TailHeadXorInverse,
+            MixStep::MiddleShift => MixStep::MiddleShift,
+            MixStep::TailHeadXorInverse => MixStep::TailHeadXor,
+            MixStep::DownUpSumInverse => MixStep::DownUpSum,
+            MixStep::RotateRight => MixStep::RotateLeft,
+            MixStep::SubtractIndex => MixStep::AddIndex,
+            MixStep::UpDownXorInverse => MixStep::UpDownXor,
+            MixStep::HeadTailSumInverse => MixStep::HeadTailSum,
+        }
+    }
+
+    /// Returns whether the step changes data of length `len`, as the prefix passes only run if the data is longer
+    /// than their width.
+    const fn runs(self, len: usize) -> bool {
+        match self {
+            MixStep::HeadTailSum | MixStep::TailHeadXor | MixStep::TailHeadXorInverse | MixStep::HeadTailSumInverse => len > 1,
+            MixStep::UpDownXor | MixStep::UpDownXorInverse => len > Mixer::MIX_WIDTH_A,
+            MixStep::DownUpSum | MixStep::DownUpSumInverse => len > Mixer::MIX_WIDTH_B,
+            MixStep::AddIndex | MixStep::SubtractIndex => len > 0,
+            MixStep::RotateLeft | MixStep::MiddleShift | MixStep::RotateRight => len >= Padder::ALIGNMENT,
+        }
+    }
+
+    /// Returns the name of the step reported by `Mixer::mix_profiled`.
+    #[cfg(feature = "profile")]
+    const fn profile_name(self) -> &'static str {
+        match self {
+            MixStep::HeadTailSum => "step 1: h2t prefix sum",
+            MixStep::UpDownXor => "step 2: 3-width u2d prefix xor",
+            MixStep::AddIndex => "step 3: add index",
+            MixStep::RotateLeft => "shift 1: block rotl",
+            MixStep::DownUpSum => "step 4: 6-width d2u prefix sum",
+            MixStep::TailHeadXor => "step 5: t2h prefix xor",
+            MixStep::MiddleShift => "shift 2: middle shift",
+            MixStep::TailHeadXorInverse => "step 5r: t2h inverse prefix xor",
+            MixStep::DownUpSumInverse => "step 4r: 6-width d2u inverse prefix sum",
+            MixStep::RotateRight => "shift 1r: block rotr",
+            MixStep::SubtractIndex => "step 3r: subtract index",
+            MixStep::UpDownXorInverse => "step 2r: 3-width u2d inverse prefix xor",
+            MixStep::HeadTailSumInverse => "step 1r: h2t inverse prefix sum",
+        }
+    }
+}
+
 /// The `Mixer` struct represents a mixer that operates on an owned byte slice.
+///
+/// Mixing spreads every byte over the whole block, including the length field at its beginning. Hence nothing can be
+/// read from a mixed block, not even its length, before the whole block is unmixed, the end of a mixed block cannot be
+/// told from its beginning, and a truncated mixed block is indistinguishable from a corrupted one.
+///
+/// The content of a `Mixer` is never empty, as there is nothing to mix in an empty slice, and `encode` never produces
+/// one either.
 pub struct Mixer {
     content: Box<[u8]>,
 }
 
 impl Mixer {
+    /// The width of the up-to-down prefix xor in the mix schedule, i.e. the distance between each byte and the byte
+    /// it is xored with.
+    pub const MIX_WIDTH_A: usize = 3;
+
+    /// The width of the down-to-up prefix sum in the mix schedule, i.e. the distance between each byte and the byte
+    /// added to it.
+    pub const MIX_WIDTH_B: usize = 6;
+
+    /// Checks whether a length can be mixed, i.e. whether it is a multiple of `Padder::ALIGNMENT`.
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - The length of the data to be mixed.
+    ///
+    /// # Returns
+    ///
+    /// * `true` if `len` is a multiple of `Padder::ALIGNMENT`, `false` otherwise.
+    pub const fn is_aligned(len: usize) -> bool {
+        len.is_multiple_of(Padder::ALIGNMENT)
+    }
+
     /// Creates a new `Mixer` from a byte slice, copying the input data.
     ///
     /// # Arguments
@@ -70,12 +226,13 @@ impl Mixer {
     ///
     /// # Returns
     ///
-    /// * An `Option` containing a new `Mixer` if the length of the input data is a multiple of `Padder::ALIGNMENT`.
-    /// * `None` if the length of the input data is not a multiple of `Padder::ALIGNMENT`.
+    /// * An `Option` containing a new `Mixer` if the input data is non-empty and its length is a multiple of
+    ///   `Padder::ALIGNMENT`.
+    /// * `None` if the input data is empty, or its length is not a multiple of `Padder::ALIGNMENT`.
     pub fn new_with_copy(input: impl AsRef<[u8]>) -> Option<Self> {
         let input = input.as_ref();
 
-        if input.len() % Padder::ALIGNMENT != 0 {
+        if input.is_empty() || !Self::is_aligned(input.len()) {
             None
         } else {
             Self::new(input.as_ref().to_vec())
@@ -90,12 +247,13 @@ impl Mixer {
     ///
     /// # Returns
     ///
-    /// * An `Option` containing a new `Mixer` if the length of the input data is a multiple of `Padder::ALIGNMENT`.
-    /// * `None` if the length of the input data is not a multiple of `Padder::ALIGNMENT`.
+    /// * An `Option` containing a new `Mixer` if the input data is non-empty and its length is a multiple of
+    ///   `Padder::ALIGNMENT`.
+    /// * `None` if the input data is empty, or its length is not a multiple of `Padder::ALIGNMENT`.
     pub fn new(input: impl Into<Box<[u8]>>) -> Option<Self> {
         let input = input.into();
 
-        if input.len() % Padder::ALIGNMENT != 0 {
+        if input.is_empty() || !Self::is_aligned(input.len()) {
             None
         } else {
             Some(Self { content: input, })
@@ -115,6 +273,28 @@ impl Mixer {
         Self::new(padder).unwrap()
     }
 
+    /// Replaces the content of the `Mixer`, so that it can be reused for another block.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A boxed slice of bytes to be used as the new content of the `Mixer`.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(())` if the input data is non-empty and its length is a multiple of `Padder::ALIGNMENT`.
+    /// * `None` if the input data is empty, or its length is not a multiple of `Padder::ALIGNMENT`. The content of
+    ///   the `Mixer` is left unchanged.
+    pub fn reset(&mut self, input: impl Into<Box<[u8]>>) -> Option<()> {
+        let input = input.into();
+
+        if input.is_empty() || !Self::is_aligned(input.len()) {
+            None
+        } else {
+            self.content = input;
+            Some(())
+        }
+    }
+
     /// Performs a left rotation on a 3-byte block by a specified number of bits.
     ///
     /// # Arguments
@@ -143,6 +323,36 @@ impl Mixer {
         content[0] = (content[0] >> shift) | (carry << remain);
     }
 
+    /// Rotates each 3-byte block left by `ROTATION_SHIFTS`, cycling through the table by block.
+    ///
+    /// Blocks are processed in groups of `ROTATION_SHIFTS.len()`, so the shift of each block in a group is constant.
+    fn rotate_blocks_left(content: &mut [u8]) {
+        let mut groups = content.chunks_exact_mut(3 * ROTATION_SHIFTS.len());
+        for group in &mut groups {
+            for (block, &shift) in group.chunks_exact_mut(3).zip(ROTATION_SHIFTS.iter()) {
+                Self::block_be_rotl(block.try_into().unwrap(), shift);
+            }
+        }
+
+        for (block, &shift) in groups.into_remainder().chunks_exact_mut(3).zip(ROTATION_SHIFTS.iter()) {
+            Self::block_be_rotl(block.try_into().unwrap(), shift);
+        }
+    }
+
+    /// Rotates each 3-byte block right by `ROTATION_SHIFTS`, undoing `rotate_blocks_left`.
+    fn rotate_blocks_right(content: &mut [u8]) {
+        let mut groups = content.chunks_exact_mut(3 * ROTATION_SHIFTS.len());
+        for group in &mut groups {
+            for (block, &shift) in group.chunks_exact_mut(3).zip(ROTATION_SHIFTS.iter()) {
+                Self::block_be_rotr(block.try_into().unwrap(), shift);
+            }
+        }
+
+        for (block, &shift) in groups.into_remainder().chunks_exact_mut(3).zip(ROTATION_SHIFTS.iter()) {
+            Self::block_be_rotr(block.try_into().unwrap(), shift);
+        }
+    }
+
     /// Reverses the bits of the middle byte in a 3-byte block and swaps the first and last bytes.
     ///
     /// # Arguments
@@ -151,9 +361,7 @@ impl Mixer {
     fn middle_shift(content: &mut [u8; 3]) {
         content[1] = content[1].reverse_bits();
 
-        let temp = content[0];
-        content[0] = content[2];
-        content[2] = temp;
+        content.swap(0, 2);
     }
 
     /// Applies a series of mixing rules to the content of the `Mixer`.
@@ -161,13 +369,22 @@ impl Mixer {
     /// The rules include various operations such as prefix sums, xors, shifts, and additions and subtractions of indices.
     /// The rules are applied in a specific order such that another call to `mix` will reverse the effects of the first call.
     pub fn mix(&mut self) {
+        Self::mix_in_place(&mut self.content);
+    }
+
+    /// Applies the mixing rules of `mix` to a byte slice in place, whose length must be a non-zero multiple of
+    /// `Padder::ALIGNMENT`.
+    ///
+    /// This allows mixing data which is not owned by a `Mixer`, such as a buffer on the stack.
+    #[inline(always)]
+    pub(crate) fn mix_in_place(content: &mut [u8]) {
         // step   1: head-to-tail prefix sum
         // step   2: 3-width up-to-down prefix xor
         // step   3: add index to each byte
         // shift  1: shl by (row_id_0_based * 2 + 1) % 8 bits in 3-byte big-endian blocks
         // step   4: 6-width down-to-up prefix sum
         // step   5: tail-to-head prefix xor
-        // shift  2:
+        // shift  2: reverse the bits of the middle byte and swap the outer bytes of 3-byte blocks
         // step  5r: tail-to-head inverse prefix xor
         // step  4r: 6-width down-to-up inverse prefix sum
         // shift 1r: shr by (row_id_0_based * 2 + 1) % 8 bits in 3-byte big-endian blocks
@@ -175,19 +392,211 @@ impl Mixer {
         // step  2r: 3-width up-to-down inverse prefix xor
         // step  1r: head-to-tail inverse prefix sum
 
-        mix_rule!(self.content,     h2t, i, this, prev, { *this = this.wrapping_add(prev); });
-        mix_rule!(self.content,     u2d, 3, i, this, prev, { *this ^= prev; });
-        mix_rule!(self.content,    byte, i, this, { *this = this.wrapping_add(i as u8); });
-        mix_rule!(self.content,   block, 3, i, this, { Self::block_be_rotl(this, (i / 3 * 2 + 1) & 0x7); });
-        mix_rule!(self.content,     d2u, 6, i, this, next, { *this = this.wrapping_add(next); });
-        mix_rule!(self.content,     t2h, i, this, next, { *this ^= next; });
-        mix_rule!(self.content,   block, 3, i, this, { Self::middle_shift(this); });
-        mix_rule!(self.content,    t2hr, i, this, prev, { *this ^= prev; });
-        mix_rule!(self.content,    d2ur, 6, i, this, prev, { *this = this.wrapping_sub(prev); });
-        mix_rule!(self.content,   block, 3, i, this, { Self::block_be_rotr(this, (i / 3 * 2 + 1) & 0x7); });
-        mix_rule!(self.content,    byte, i, this, { *this = this.wrapping_sub(i as u8); });
-        mix_rule!(self.content,    u2dr, 3, i, this, prev, { *this ^= prev; });
-        mix_rule!(self.content,    h2tr, i, this, prev, { *this = this.wrapping_sub(prev); });
+        mix_rule!(content,     h2t, i, this, prev, { *this = this.wrapping_add(prev); });
+        mix_rule!(content,     u2d, Self::MIX_WIDTH_A, i, this, prev, { *this ^= prev; });
+        mix_rule!(content,    byte, i, this, { *this = this.wrapping_add(i as u8); });
+        Self::rotate_blocks_left(content);
+        mix_rule!(content,     d2u, Self::MIX_WIDTH_B, i, this, next, { *this = this.wrapping_add(next); });
+        mix_rule!(content,     t2h, i, this, next, { *this ^= next; });
+        mix_rule!(content,   block, 3, i, this, { Self::middle_shift(this); });
+        mix_rule!(content,    t2hr, i, this, prev, { *this ^= prev; });
+        mix_rule!(content,    d2ur, Self::MIX_WIDTH_B, i, this, prev, { *this = this.wrapping_sub(prev); });
+        Self::rotate_blocks_right(content);
+        mix_rule!(content,    byte, i, this, { *this = this.wrapping_sub(i as u8); });
+        mix_rule!(content,    u2dr, Self::MIX_WIDTH_A, i, this, prev, { *this ^= prev; });
+        mix_rule!(content,    h2tr, i, this, prev, { *this = this.wrapping_sub(prev); });
+    }
+
+    /// Applies a single step of `MixStep::SCHEDULE` to a byte slice in place, like the corresponding line of
+    /// `mix_in_place`.
+    ///
+    /// If `key` is given, the index added or subtracted by step 3 and its inverse is whitened by the key bytes, as
+    /// `mix_keyed` does.
+    fn apply_step(content: &mut [u8], step: MixStep, key: Option<&[u8; 8]>) {
+        match (step, key) {
+            (MixStep::HeadTailSum, _) => mix_rule!(content, h2t, i, this, prev, { *this = this.wrapping_add(prev); }),
+            (MixStep::UpDownXor, _) => mix_rule!(content, u2d, Self::MIX_WIDTH_A, i, this, prev, { *this ^= prev; }),
+            (MixStep::AddIndex, None) => mix_rule!(content, byte, i, this, { *this = this.wrapping_add(i as u8); }),
+            (MixStep::AddIndex, Some(key)) => mix_rule!(content, byte, i, this, { *this = this.wrapping_add((i as u8).wrapping_add(key[i % 8])); }),
+            (MixStep::RotateLeft, _) => Self::rotate_blocks_left(content),
+            (MixStep::DownUpSum, _) => mix_rule!(content, d2u, Self::MIX_WIDTH_B, i, this, next, { *this = this.wrapping_add(next); }),
+            (MixStep::TailHeadXor, _) => mix_rule!(content, t2h, i, this, next, { *this ^= next; }),
+            (MixStep::MiddleShift, _) => mix_rule!(content, block, 3, i, this, { Self::middle_shift(this); }),
+            (MixStep::TailHeadXorInverse, _) => mix_rule!(content, t2hr, i, this, prev, { *this ^= prev; }),
+            (MixStep::DownUpSumInverse, _) => mix_rule!(content, d2ur, Self::MIX_WIDTH_B, i, this, prev, { *this = this.wrapping_sub(prev); }),
+            (MixStep::RotateRight, _) => Self::rotate_blocks_right(content),
+            (MixStep::SubtractIndex, None) => mix_rule!(content, byte, i, this, { *this = this.wrapping_sub(i as u8); }),
+            (MixStep::SubtractIndex, Some(key)) => mix_rule!(content, byte, i, this, { *this = this.wrapping_sub((i as u8).wrapping_add(key[i % 8])); }),
+            (MixStep::UpDownXorInverse, _) => mix_rule!(content, u2dr, Self::MIX_WIDTH_A, i, this, prev, { *this ^= prev; }),
+            (MixStep::HeadTailSumInverse, _) => mix_rule!(content, h2tr, i, this, prev, { *this = this.wrapping_sub(prev); }),
+        }
+    }
+
+    /// Applies the mixing rules of `mix` except step 3 and its inverse, which add and subtract the index of each byte.
+    ///
+    /// This is an analysis tool for comparing mixes of data placed at different offsets, and is not used by `encode` or
+    /// `decode`. Like `mix`, it is its own inverse, so a second call restores the content.
+    pub fn mix_without_index_step(&mut self) {
+        for step in MixStep::SCHEDULE {
+            if !matches!(step, MixStep::AddIndex | MixStep::SubtractIndex) {
+                Self::apply_step(&mut self.content, step, None);
+            }
+        }
+    }
+
+    /// Applies the same mixing rules as `mix`, timing each step.
+    ///
+    /// This is meant for performance debugging only; `mix` itself is not instrumented.
+    #[cfg(feature = "profile")]
+    pub fn mix_profiled(&mut self) -> MixProfile {
+        let steps = MixStep::SCHEDULE.into_iter().map(|step| {
+            let start = Instant::now();
+            Self::apply_step(&mut self.content, step, None);
+            (step.profile_name(), start.elapsed())
+        }).collect();
+
+        MixProfile { steps }
+    }
+
+    /// Derives the order of the steps before the middle shift from a key.
+    ///
+    /// The order is a Fisher-Yates shuffle of the first half of `MixStep::SCHEDULE`, driven by splitmix64 seeded with
+    /// the key.
+    fn keyed_schedule(key: u64) -> [MixStep; 6] {
+        let mut schedule: [MixStep; 6] = MixStep::SCHEDULE[..6].try_into().unwrap();
+        let mut state = key;
+
+        for i in (1..schedule.len()).rev() {
+            state = state.wrapping_add(0x9e3779b97f4a7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            z ^= z >> 31;
+
+            schedule.swap(i, (z % (i as u64 + 1)) as usize);
+        }
+
+        schedule
+    }
+
+    /// Applies the mixing rules in a key-dependent way.
+    ///
+    /// The key selects the order of the steps before the middle shift, and is added to the content along with the
+    /// indices. The steps are undone in reverse order after the middle shift, so another call to `mix_keyed` with the
+    /// same key reverses the effects of the first call.
+    ///
+    /// This only makes the output depend on a shared key. It is not encryption: there are only 720 orderings, and the
+    /// key bytes are merely added to the content, so it offers no protection against anyone who tries to recover it.
+    pub fn mix_keyed(&mut self, key: u64) {
+        let schedule = Self::keyed_schedule(key);
+        let key = key.to_le_bytes();
+
+        for step in schedule {
+            Self::apply_step(&mut self.content, step, Some(&key));
+        }
+        Self::apply_step(&mut self.content, MixStep::MiddleShift, Some(&key));
+        for step in schedule.into_iter().rev() {
+            Self::apply_step(&mut self.content, step.inverse(), Some(&key));
+        }
+    }
+
+    /// Returns the length of the content of the `Mixer`.
+    ///
+    /// # Returns
+    ///
+    /// * A usize value representing the length of the content of the `Mixer`.
+    pub fn content_len(&self) -> usize {
+        self.content.len()
+    }
+
+    /// Returns the steps of `mix` which actually change data of length `len`, in the order they are applied.
+    ///
+    /// The prefix passes only run if the data is longer than their width, so for short data some of the mixing is
+    /// skipped entirely, e.g. the down-to-up prefix sum and its inverse for data of at most `MIX_WIDTH_B` bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - The length of the data to be mixed.
+    ///
+    /// # Returns
+    ///
+    /// * A vector of the steps which run for data of length `len`, empty if `len` is 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gesist::mixer::{Mixer, MixStep};
+    ///
+    /// assert_eq!(Mixer::effective_schedule(300).len(), 13);
+    /// assert!(!Mixer::effective_schedule(6).contains(&MixStep::DownUpSum));
+    /// ```
+    pub fn effective_schedule(len: usize) -> Vec<MixStep> {
+        MixStep::SCHEDULE.into_iter().filter(|step| step.runs(len)).collect()
+    }
+
+    /// Returns the range of the mixed output that may change when the bytes in `edit` change before mixing.
+    ///
+    /// Mixing is deliberately diffusive: the head-to-tail prefix sum carries a change to every later byte, and the
+    /// tail-to-head prefix xor then carries it back to every earlier byte. So any non-empty edit may change the whole
+    /// output, and there is no cheaper way to update a mixed block after a partial edit than mixing it again.
+    ///
+    /// # Arguments
+    ///
+    /// * `edit` - The range of the content before mixing that is edited.
+    ///
+    /// # Returns
+    ///
+    /// * The whole range of the content if `edit` is non-empty, or an empty range otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gesist::mixer::Mixer;
+    ///
+    /// let mixer = Mixer::new(vec![0; 300]).unwrap();
+    /// assert_eq!(mixer.mix_affected_range(150..151), 0..300);
+    /// assert!(mixer.mix_affected_range(150..150).is_empty());
+    /// ```
+    pub fn mix_affected_range(&self, edit: Range<usize>) -> Range<usize> {
+        if edit.is_empty() {
+            0..0
+        } else {
+            0..self.content_len()
+        }
+    }
+
+    /// Returns the number of blocks in the content of the `Mixer`, which the block-wise rules operate on.
+    ///
+    /// # Returns
+    ///
+    /// * A usize value representing the length of the content divided by `Padder::ALIGNMENT`.
+    pub fn block_count(&self) -> usize {
+        self.content_len() / Padder::ALIGNMENT
+    }
+
+    /// Mixes a random buffer of `sample_len` bytes twice, and asserts that the result equals the original buffer.
+    ///
+    /// Decoding relies on `mix` being its own inverse, so this guards against a change of the mixing rules breaking
+    /// that property. It is available in tests and with the `debug-assertions` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `sample_len` - The length of the random buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sample_len` is 0 or not a multiple of `Padder::ALIGNMENT`, or if mixing twice does not restore the
+    /// buffer.
+    #[cfg(any(test, feature = "debug-assertions"))]
+    pub fn verify_involution(sample_len: usize) {
+        let mut sample = vec![0; sample_len];
+        rand::Rng::fill(&mut rand::thread_rng(), sample.as_mut_slice());
+
+        let mut mixer = Self::new_with_copy(&sample).expect("sample length must be positive and aligned");
+        mixer.mix();
+        mixer.mix();
+
+        assert_eq!(mixer.as_slice(), sample.as_slice(), "mixing twice does not restore the content");
     }
 
     /// Returns a slice of the content of the `Mixer`.
@@ -237,14 +646,97 @@ impl AsRef<[u8]> for Mixer {
     }
 }
 
-/// Implementation of the `Into` trait for the `Mixer` struct.
-impl Into<Box<[u8]>> for Mixer {
+/// Implementation of the `From` trait for converting a `Mixer` into a boxed slice.
+impl From<Mixer> for Box<[u8]> {
     /// Consumes the `Mixer` and returns the content as a boxed slice.
     ///
     /// # Returns
     ///
     /// * A boxed slice representing the entire content of the `Mixer`.
-    fn into(self) -> Box<[u8]> {
-        self.content
+    fn from(mixer: Mixer) -> Self {
+        mixer.content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::encode;
+    use crate::mixer::Mixer;
+    use crate::padder::Padder;
+
+    fn mixed(data: &[u8]) -> Box<[u8]> {
+        let mut mix = Mixer::new_with_copy(data).unwrap();
+        mix.mix();
+        mix.into()
+    }
+
+    #[test]
+    fn mix_is_involution() {
+        for sample_len in [3, 6, 9, 300, 3000] {
+            Mixer::verify_involution(sample_len);
+        }
+    }
+
+    #[test]
+    fn mix_without_index_step_is_involution() {
+        let data: Vec<u8> = (0..30).collect();
+        let mut mix = Mixer::new_with_copy(&data).unwrap();
+        mix.mix_without_index_step();
+        assert_ne!(mix.as_slice(), mixed(&data).as_ref());
+        mix.mix_without_index_step();
+        assert_eq!(mix.as_slice(), data.as_slice());
+    }
+
+    #[test]
+    fn effective_mix_schedule() {
+        use crate::mixer::MixStep;
+
+        assert!(Mixer::effective_schedule(0).is_empty());
+        let short = Mixer::effective_schedule(3);
+        assert_eq!(short.len(), 9);
+        assert!(!short.contains(&MixStep::UpDownXor) && !short.contains(&MixStep::DownUpSumInverse));
+        assert_eq!(Mixer::effective_schedule(6).len(), 11);
+        assert_eq!(Mixer::effective_schedule(9).len(), 13);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn schedule_matches_mix() {
+        use crate::mixer::MixStep;
+
+        for len in [3, 6, 9, 30, 3000] {
+            let mut data: Vec<u8> = (0..len).map(|i| (i * 131 + 7) as u8).collect();
+            let expected = mixed(&data);
+            for step in MixStep::SCHEDULE {
+                Mixer::apply_step(&mut data, step, None);
+            }
+            assert_eq!(data.as_slice(), expected.as_ref());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "profile")]
+    fn profiled_mix_matches_mix() {
+        let mut profiled = Mixer::new_from_padder(Padder::new([0x5a; 100]));
+        assert_eq!(profiled.mix_profiled().steps.len(), 13);
+        assert_eq!(profiled.as_slice(), encode([0x5a; 100]).as_ref());
+
+        for len in [3, 6, 9, 30, 3000] {
+            let data: Vec<u8> = (0..len).map(|i| (i * 131 + 7) as u8).collect();
+            let mut profiled = Mixer::new_with_copy(&data).unwrap();
+            profiled.mix_profiled();
+            assert_eq!(profiled.as_slice(), mixed(&data).as_ref());
+        }
+    }
+
+    #[test]
+    fn reset_mixer_matches_new_mixer() {
+        let mut mixer = Mixer::new_from_padder(Padder::new(b"hello"));
+        mixer.mix();
+        assert!(mixer.reset(vec![0; 4]).is_none());
+        assert_eq!(mixer.as_slice(), encode(b"hello").as_ref());
+
+        assert!(mixer.reset(Padder::new(b"world")).is_some());
+        mixer.mix();
+        assert_eq!(mixer.as_slice(), encode(b"world").as_ref());
+    }
+}
@@ -2,8 +2,10 @@ use std::ops::{Deref, DerefMut};
 use std::borrow::Borrow;
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
-use std::io::Cursor;
-use crc::{Crc, CRC_8_SAE_J1850};
+use std::io::{self, Cursor, Write};
+use crc::{Crc, Digest, CRC_8_SAE_J1850};
+
+use crate::mixer::Mixer;
 
 /// This function calculates the size of a leb128 encoded integer.
 ///
@@ -33,12 +35,214 @@ pub const fn leb128_size(input: usize) -> usize {
     }
 }
 
+/// The maximum size of the leb128-encoded length field, i.e. the size of the leb128 encoding of `usize::MAX`.
+pub const MAX_LEB128_LEN: usize = (usize::BITS as usize).div_ceil(7);
+
+/// This function returns the number of bytes which always contain the whole length field of a padded data block, or
+/// the whole block if it is shorter.
+///
+/// This is `MAX_LEB128_LEN` rounded up to a multiple of `Padder::ALIGNMENT`, so a reader of unmixed blocks can buffer
+/// this many bytes before parsing the length field with `read_length_prefix` or `decoded_block_len`. Blocks read from
+/// a stream are mixed, and must be unmixed as a whole first, see `Mixer`.
+///
+/// # Example
+///
+/// ```
+/// use gesist::padder::{min_header_bytes, MAX_LEB128_LEN};
+///
+/// assert!(min_header_bytes() >= MAX_LEB128_LEN);
+/// assert_eq!(min_header_bytes() % 3, 0);
+/// ```
+pub const fn min_header_bytes() -> usize {
+    MAX_LEB128_LEN.next_multiple_of(Padder::ALIGNMENT)
+}
+
+/// This function reads the leb128-encoded length field at the beginning of a padded data block.
+///
+/// Nothing other than the length field is validated.
+///
+/// # Arguments
+///
+/// * `raw` - A byte slice starting with a padded data block.
+///
+/// # Returns
+///
+/// * A `Result` which is:
+///     - `Ok` containing the payload size and the number of bytes of the length field.
+///     - `Err` containing `PaddingValidationError::BadLengthField` if the length field cannot be parsed, is not the
+///       shortest encoding of its value, or declares a size which does not fit in `usize` on the current target.
+///
+/// # Example
+///
+/// ```
+/// use gesist::padder::{read_length_prefix, Padder};
+///
+/// let raw: Box<[u8]> = Padder::new([0; 300]).into();
+/// assert_eq!(read_length_prefix(&raw).unwrap(), (300, 2));
+/// ```
+pub fn read_length_prefix(raw: &[u8]) -> Result<(usize, usize), PaddingValidationError> {
+    let mut cursor = Cursor::new(raw);
+    let payload_size = leb128::read::unsigned(&mut cursor).map_err(|_| PaddingValidationError::BadLengthField)?;
+    // on 32-bit targets a declared size above `usize::MAX` would otherwise be truncated to a small, valid-looking one
+    let payload_size = usize::try_from(payload_size).map_err(|_| PaddingValidationError::BadLengthField)?;
+    // an over-long encoding, e.g. `[0x9e, 0x80, 0x80, 0x00]` for 30, would push the payload past the padded size
+    let leb128_len = cursor.position() as usize;
+    if leb128_len != leb128_size(payload_size).max(1) {
+        return Err(PaddingValidationError::BadLengthField);
+    }
+
+    Ok((payload_size, leb128_len))
+}
+
+/// This function returns the length of the padded data block at the beginning of `raw`, as declared by its length
+/// field, without validating the rest of the block.
+///
+/// `raw` must be unmixed, see `Mixer`. The filler, if any, is not included, and `raw` may be shorter than the returned
+/// length, e.g. when only the beginning of a block has been read.
+///
+/// # Arguments
+///
+/// * `raw` - A byte slice starting with an unmixed padded data block.
+///
+/// # Returns
+///
+/// * A `Result` which is:
+///     - `Ok` containing the length of the padded data block.
+///     - `Err` containing `PaddingValidationError::BadLengthField` if the length field cannot be parsed, or the
+///       declared length does not fit in a usize.
+///
+/// # Example
+///
+/// ```
+/// use gesist::padder::{decoded_block_len, Padder};
+///
+/// let raw: Box<[u8]> = Padder::new([0; 300]).into();
+/// assert_eq!(decoded_block_len(&raw[..4]).unwrap(), 303);
+/// ```
+pub fn decoded_block_len(raw: &[u8]) -> Result<usize, PaddingValidationError> {
+    let (payload_size, _) = read_length_prefix(raw)?;
+    Padder::checked_padded_size(payload_size).ok_or(PaddingValidationError::BadLengthField)
+}
+
+/// This function checks that the length field of a padded data block agrees with the length of the block, without
+/// validating the checksum.
+///
+/// `raw` must be unmixed, see `Mixer`. Blocks with filler or an extended checksum field are rejected, as their length
+/// differs from the padded size.
+///
+/// # Arguments
+///
+/// * `raw` - A byte slice containing exactly one unmixed padded data block.
+///
+/// # Returns
+///
+/// * A `Result` which is:
+///     - `Ok` containing the payload size if the padded size of the declared payload size is the length of `raw`.
+///     - `Err` containing `PaddingValidationError::BadLengthField` if the length field cannot be parsed, or
+///       `PaddingValidationError::UnexpectedPaddedLength` if the lengths disagree.
+///
+/// # Example
+///
+/// ```
+/// use gesist::padder::{validate_length_field, Padder};
+///
+/// let raw: Box<[u8]> = Padder::new([0; 300]).into();
+/// assert_eq!(validate_length_field(&raw).unwrap(), 300);
+/// assert!(validate_length_field(&raw[..300]).is_err());
+/// ```
+pub fn validate_length_field(raw: &[u8]) -> Result<usize, PaddingValidationError> {
+    let (payload_size, _) = read_length_prefix(raw)?;
+    let expected = Padder::checked_padded_size(payload_size).ok_or(PaddingValidationError::BadLengthField)?;
+    if expected != raw.len() {
+        return Err(PaddingValidationError::UnexpectedPaddedLength { payload_size, expected, actual: raw.len() });
+    }
+
+    Ok(payload_size)
+}
+
+/// This function fills `out` with the checksum of `payload`, the same way the checksum field of a `Padder` is filled.
+///
+/// The first byte is the CRC of the payload, and each following byte is the previous one plus one, wrapping around.
+/// No alignment is required, so it can be used on arbitrary data.
+///
+/// # Arguments
+///
+/// * `payload` - The data to calculate the checksum of.
+/// * `out` - The buffer to be filled, whose length is the desired number of checksum bytes.
+///
+/// # Example
+///
+/// ```
+/// use gesist::padder::{fill_checksum, Padder};
+///
+/// let padder = Padder::new(b"hello");
+/// let mut checksum = [0; 3];
+/// fill_checksum(b"hello", &mut checksum);
+///
+/// assert_eq!(padder.checksum(), checksum);
+/// ```
+pub fn fill_checksum(payload: &[u8], out: &mut [u8]) {
+    fill_checksum_from_crc(Padder::CRC.checksum(payload), out);
+}
+
+/// This function checks whether two payloads have the same CRC, and thus the same checksum field when padded to the
+/// same length.
+///
+/// The CRC is only 8 bits wide, so about one in 256 pairs of different payloads collide, and the checksum field only
+/// detects accidental corruption with the same probability.
+///
+/// # Example
+///
+/// ```
+/// use gesist::padder::checksum_collides;
+///
+/// assert!(checksum_collides(b"hello", b"hello"));
+/// assert!(!checksum_collides(b"hello", b"world"));
+/// ```
+pub fn checksum_collides(a: &[u8], b: &[u8]) -> bool {
+    Padder::CRC.checksum(a) == Padder::CRC.checksum(b)
+}
+
+/// This function estimates the expected number of pairs of payloads with the same checksum in a set of payloads,
+/// using the birthday approximation `n * (n - 1) / 2 / 2^bits`.
+///
+/// The checksum field of gesist is 8 bits wide, so `checksum_bits` is 8 for it; other values allow comparing with
+/// wider checksums.
+///
+/// # Example
+///
+/// ```
+/// use gesist::padder::expected_collisions;
+///
+/// assert_eq!(expected_collisions(2, 8), 1.0 / 256.0);
+/// assert!(expected_collisions(1000, 8) > 1000.0);
+/// ```
+pub fn expected_collisions(num_payloads: usize, checksum_bits: u32) -> f64 {
+    let n = num_payloads as f64;
+    n * (n - 1.0).max(0.0) / 2.0 / 2f64.powi(checksum_bits as i32)
+}
+
+/// Fills `out` with the index-incremented `crc`.
+fn fill_checksum_from_crc(crc: u8, out: &mut [u8]) {
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = crc.wrapping_add(i as u8);
+    }
+}
+
 /// `Padder` is a structure that represents a padded block of data.
 ///
 /// Padding here includes a prefixing leb128-encoded length field, and a suffixing checksum field.
+///
+/// The length field is read and written one byte at a time, so it has no byte order, and blocks are the same on
+/// big-endian and little-endian machines. There is no fixed-width length field.
+///
+/// Optionally, the checksum field can be followed by filler bytes, which are not covered by the checksum.
+/// The size of the filler is always a multiple of `Padder::ALIGNMENT`.
+#[derive(Clone)]
 pub struct Padder {
     leb128_size: usize,
     size: usize,
+    filler: usize,
     content: Box<[u8]>,
 }
 
@@ -46,16 +250,59 @@ pub struct Padder {
 ///
 /// This structure is used to ensure that the checksum of the `Padder` is recalculated
 /// when the `Padder` is mutated.
+///
+/// It also implements `io::Write`, writing into the payload from the beginning.
 pub struct PadderMutGuard<'a> {
     padder: &'a mut Padder,
+    position: usize,
 }
 
-#[derive(Clone)]
+/// `PadderStreamGuard` is a structure that writes the payload of a `Padder` sequentially.
+///
+/// Unlike `PadderMutGuard`, it updates the checksum incrementally as bytes are written, so no extra pass over the
+/// payload is needed when it is finished, as long as the whole payload has been written.
+pub struct PadderStreamGuard<'a> {
+    padder: &'a mut Padder,
+    position: usize,
+    digest: Option<Digest<'static, u8>>,
+}
+
+/// `PayloadPreview` wraps a payload to display it as a length summary followed by the hex of its first bytes, e.g.
+/// `[12 bytes] 68 65 6c 6c 6f 2c 20 77 6f 72 6c 64`.
+///
+/// At most `PayloadPreview::LIMIT` bytes are shown, followed by `…` if the payload is longer.
+#[derive(Clone, Copy, Debug)]
+pub struct PayloadPreview<'a>(pub &'a [u8]);
+
+impl PayloadPreview<'_> {
+    /// The maximum number of bytes shown.
+    pub const LIMIT: usize = 16;
+}
+
+impl Display for PayloadPreview<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{} bytes]", self.0.len())?;
+        for byte in self.0.iter().take(Self::LIMIT) {
+            write!(f, " {:02x}", byte)?;
+        }
+
+        if self.0.len() > Self::LIMIT {
+            write!(f, " …")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum PaddingValidationError {
     NotAligned { length: usize },
     BadLengthField,
     UnexpectedPaddedLength { payload_size: usize, expected: usize, actual: usize },
     InvalidChecksum { offset: usize },
+    PayloadTooLarge { declared: usize, limit: usize },
+    TagMismatch { expected: [u8; 4], actual: Option<[u8; 4]> },
+    UnexpectedPayloadLength { expected: usize, actual: usize },
 }
 
 impl Debug for PaddingValidationError {
@@ -66,18 +313,72 @@ impl Debug for PaddingValidationError {
             PaddingValidationError::UnexpectedPaddedLength { payload_size, expected, actual } =>
                 write!(f, "Unexpected padded length for payload size {}, {} expected, {} actual", payload_size, expected, actual),
             PaddingValidationError::InvalidChecksum { offset } => write!(f, "Invalid checksum at offset {}", offset),
+            PaddingValidationError::PayloadTooLarge { declared, limit } =>
+                write!(f, "Declared payload size {} exceeds the limit {}", declared, limit),
+            PaddingValidationError::TagMismatch { expected, actual: Some(actual) } =>
+                write!(f, "Tag mismatch, {:02x?} expected, {:02x?} actual", expected, actual),
+            PaddingValidationError::TagMismatch { expected, actual: None } =>
+                write!(f, "Tag mismatch, {:02x?} expected, payload shorter than a tag", expected),
+            PaddingValidationError::UnexpectedPayloadLength { expected, actual } =>
+                write!(f, "Unexpected payload length, {} expected, {} actual", expected, actual),
         }
     }
 }
 
 impl Display for PaddingValidationError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        <Self as Debug>::fmt(&self, f)
+        <Self as Debug>::fmt(self, f)
     }
 }
 
 impl Error for PaddingValidationError {}
 
+/// `PaddingValidationErrorKind` is the kind of a `PaddingValidationError`, without its details, for simpler matching.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PaddingValidationErrorKind {
+    NotAligned,
+    BadLengthField,
+    UnexpectedPaddedLength,
+    InvalidChecksum,
+    PayloadTooLarge,
+    TagMismatch,
+    UnexpectedPayloadLength,
+}
+
+impl PaddingValidationError {
+    /// Returns the kind of the error.
+    pub fn kind(&self) -> PaddingValidationErrorKind {
+        match self {
+            PaddingValidationError::NotAligned { .. } => PaddingValidationErrorKind::NotAligned,
+            PaddingValidationError::BadLengthField => PaddingValidationErrorKind::BadLengthField,
+            PaddingValidationError::UnexpectedPaddedLength { .. } => PaddingValidationErrorKind::UnexpectedPaddedLength,
+            PaddingValidationError::InvalidChecksum { .. } => PaddingValidationErrorKind::InvalidChecksum,
+            PaddingValidationError::PayloadTooLarge { .. } => PaddingValidationErrorKind::PayloadTooLarge,
+            PaddingValidationError::TagMismatch { .. } => PaddingValidationErrorKind::TagMismatch,
+            PaddingValidationError::UnexpectedPayloadLength { .. } => PaddingValidationErrorKind::UnexpectedPayloadLength,
+        }
+    }
+
+    /// Returns whether the error may be caused by reading too little or too much of the input, so that reading the
+    /// input again may fix it.
+    ///
+    /// This is only the case for `NotAligned`, which depends on the input length alone. An aligned but truncated input
+    /// is indistinguishable from corrupted data, see `Mixer`, and may fail with any of the other errors, all of which
+    /// are considered unrecoverable.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gesist::padder::PaddingValidationError;
+    ///
+    /// assert!(PaddingValidationError::NotAligned { length: 4 }.is_recoverable());
+    /// assert!(!PaddingValidationError::InvalidChecksum { offset: 2 }.is_recoverable());
+    /// ```
+    pub fn is_recoverable(&self) -> bool {
+        matches!(self, PaddingValidationError::NotAligned { .. })
+    }
+}
+
 /// Constants and const functions for the `Padder` struct.
 impl Padder {
     /// The alignment.
@@ -104,12 +405,110 @@ impl Padder {
     /// let padded_size = Padder::padded_size(200);
     /// assert_eq!(padded_size, 204);
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the padded size does not fit in a usize. See `checked_padded_size` for a non-panicking version.
     pub const fn padded_size(input_size: usize) -> usize {
-        let size_leb128 = leb128_size(input_size);
-        let size_without_checksum = size_leb128 + input_size + 1;
-        let size_checksum = (-(size_without_checksum as isize)).rem_euclid(Self::ALIGNMENT as isize) as usize;
+        match Self::checked_padded_size(input_size) {
+            Some(padded_size) => padded_size,
+            None => panic!("padded size overflows usize"),
+        }
+    }
+
+    /// This function calculates the size of the padded data block, returning `None` on overflow.
+    ///
+    /// # Arguments
+    ///
+    /// * `input_size` - The size of the payload.
+    ///
+    /// # Returns
+    ///
+    /// * An `Option` containing the size of the padded data block, or `None` if it does not fit in a usize.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gesist::padder::Padder;
+    ///
+    /// assert_eq!(Padder::checked_padded_size(300), Some(303));
+    /// assert_eq!(Padder::checked_padded_size(usize::MAX), None);
+    /// ```
+    pub const fn checked_padded_size(input_size: usize) -> Option<usize> {
+        match input_size.checked_add(leb128_size(input_size) + 1) {
+            Some(size_without_checksum) => size_without_checksum.checked_next_multiple_of(Self::ALIGNMENT),
+            None => None,
+        }
+    }
+
+    /// Calculates the size of the padded data block with its checksum field extended to at least `min_checksum` bytes,
+    /// like `new_with_min_checksum` does, returning `None` on overflow.
+    const fn checked_padded_size_with_min_checksum(input_size: usize, min_checksum: usize) -> Option<usize> {
+        let Some(padded_size) = Self::checked_padded_size(input_size) else {
+            return None;
+        };
+
+        let checksum = padded_size - leb128_size(input_size) - input_size;
+        match min_checksum.saturating_sub(checksum).checked_next_multiple_of(Self::ALIGNMENT) {
+            Some(extension) => padded_size.checked_add(extension),
+            None => None,
+        }
+    }
+
+    /// This function checks whether a payload fits in a slot, i.e. whether its padded size is at most the slot length.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload_len` - The size of the payload.
+    /// * `slot_len` - The length of the slot.
+    ///
+    /// # Returns
+    ///
+    /// * `true` if the padded data block fits in the slot, `false` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gesist::padder::Padder;
+    ///
+    /// assert!(Padder::fits(127, 129));
+    /// assert!(!Padder::fits(128, 129));
+    /// ```
+    pub const fn fits(payload_len: usize, slot_len: usize) -> bool {
+        match Self::checked_padded_size(payload_len) {
+            Some(padded_size) => padded_size <= slot_len,
+            None => false,
+        }
+    }
+
+    /// This function calculates the size of the largest payload that fits in a slot.
+    ///
+    /// A slot shorter than `Padder::ALIGNMENT` cannot hold even an empty payload, in which case 0 is returned as well;
+    /// use `fits` to tell these cases apart.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot_len` - The length of the slot.
+    ///
+    /// # Returns
+    ///
+    /// * A usize value representing the size of the largest payload whose padded size is at most `slot_len`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gesist::padder::Padder;
+    ///
+    /// assert_eq!(Padder::remaining_capacity(129), 127);
+    /// assert_eq!(Padder::remaining_capacity(303), 300);
+    /// ```
+    pub const fn remaining_capacity(slot_len: usize) -> usize {
+        let mut payload_len = slot_len.saturating_sub(2);
+        while payload_len > 0 && !Self::fits(payload_len, slot_len) {
+            payload_len -= 1;
+        }
 
-        size_without_checksum + size_checksum
+        payload_len
     }
 
     /// The CRC used to calculate the checksum of the payload.
@@ -132,25 +531,78 @@ impl Padder {
         self.payload()
     }
 
+    /// Returns an iterator over the payload of the `Padder` in chunks of `n` bytes.
+    ///
+    /// The last chunk is shorter if the payload length is not a multiple of `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    pub fn payload_chunks(&self, n: usize) -> impl Iterator<Item = &[u8]> {
+        self.payload().chunks(n)
+    }
+
     /// Returns a `PadderMutGuard` for the `Padder`.
     ///
     /// This allows for mutation of the payload of the `Padder` while ensuring that the checksum is recalculated when the `Padder` is mutated.
-    pub fn as_mut(&mut self) -> PadderMutGuard {
-        PadderMutGuard { padder: self }
+    pub fn as_mut(&mut self) -> PadderMutGuard<'_> {
+        PadderMutGuard { padder: self, position: 0 }
+    }
+
+    /// Returns a mutable slice of the payload of the `Padder`, without recalculating the checksum afterwards.
+    ///
+    /// **The checksum is left stale.** The caller must call `recalculate_checksum` after the last edit and before
+    /// encoding the `Padder`, otherwise the encoded data fails to decode. This allows batching many edits without
+    /// running the CRC after each of them, which `as_mut` does.
+    pub fn payload_mut_raw(&mut self) -> &mut [u8] {
+        self.payload_mut()
+    }
+
+    /// Returns a `PadderStreamGuard` for the `Padder`.
+    ///
+    /// This allows for writing the payload of the `Padder` sequentially, while the checksum is updated incrementally.
+    pub fn stream(&mut self) -> PadderStreamGuard<'_> {
+        let crc: &'static Crc<u8> = &Self::CRC;
+        PadderStreamGuard { padder: self, position: 0, digest: Some(crc.digest()) }
     }
 
     /// Returns a slice of the entire content of the `Padder`.
     ///
     /// This includes the leb128 size, the payload, and the checksum.
+    ///
+    /// # Safety
+    ///
     /// This method is marked as unsafe because it exposes the raw content of the `Padder`.
     pub unsafe fn raw_slice(&self) -> &[u8] {
         &self.content
     }
 
+    /// Returns a slice of the entire content of the `Padder`, i.e. the leb128 size, the payload, the checksum and the
+    /// filler, if any.
+    ///
+    /// This is the safe counterpart of `raw_slice`, as reading the content cannot break its consistency.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gesist::padder::Padder;
+    ///
+    /// let padder = Padder::new(b"hi");
+    /// assert_eq!(padder.full_bytes().len(), 6);
+    /// assert_eq!(&padder.full_bytes()[1..3], b"hi");
+    /// ```
+    pub fn full_bytes(&self) -> &[u8] {
+        &self.content
+    }
+
     /// Returns a mutable slice of the entire content of the `Padder`.
     ///
     /// This includes the leb128 size, the payload, and the checksum.
+    ///
+    /// # Safety
+    ///
     /// This method is marked as unsafe because it exposes the raw content of the `Padder`.
+    /// The caller must keep the length field and the checksum consistent with the payload.
     pub unsafe fn raw_mut(&mut self) -> &mut [u8] {
         &mut self.content
     }
@@ -172,6 +624,54 @@ impl Padder {
     pub fn payload_length(&self) -> usize {
         self.size
     }
+
+    /// Returns a slice of the leb128-encoded length field of the `Padder`.
+    pub fn header(&self) -> &[u8] {
+        &self.content[..self.leb128_size]
+    }
+
+    /// Returns a slice of the checksum field of the `Padder`.
+    ///
+    /// The filler, if any, is not included.
+    pub fn checksum(&self) -> &[u8] {
+        &self.content[self.leb128_size + self.size..self.content.len() - self.filler]
+    }
+
+    /// Returns a slice of everything following the payload of the `Padder`, i.e. the checksum field followed by the
+    /// filler, if any.
+    ///
+    /// This is the tail of `full_bytes`. Use `checksum` to exclude the filler.
+    pub fn checksum_bytes_slice(&self) -> &[u8] {
+        &self.content[self.leb128_size + self.size..]
+    }
+
+    /// Returns a `PayloadPreview` of the payload of the `Padder`, for debugging output.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gesist::padder::Padder;
+    ///
+    /// assert_eq!(Padder::new(b"Hello").preview().to_string(), "[5 bytes] 48 65 6c 6c 6f");
+    /// ```
+    pub fn preview(&self) -> PayloadPreview<'_> {
+        PayloadPreview(self.payload())
+    }
+
+    /// Returns a slice of the filler of the `Padder`.
+    ///
+    /// The filler is not covered by the checksum, and is empty unless the `Padder` is created with filler.
+    pub fn filler(&self) -> &[u8] {
+        &self.content[self.content.len() - self.filler..]
+    }
+
+    /// Returns a mutable slice of the filler of the `Padder`.
+    ///
+    /// As the filler is not covered by the checksum, mutating it never invalidates the `Padder`.
+    pub fn filler_mut(&mut self) -> &mut [u8] {
+        let len = self.content.len();
+        &mut self.content[len - self.filler..]
+    }
 }
 
 /// Other methods
@@ -185,30 +685,74 @@ impl Padder {
     /// # Returns
     ///
     /// * A new `Padder` instance with the specified size, filled with zeroes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the padded size does not fit in a usize, or allocating it fails. See `try_new_zeroed` for a
+    /// non-panicking version.
     pub fn new_zeroed(size: usize) -> Self {
-        let padded_size = Self::padded_size(size);
-
-        debug_assert!(padded_size > size);
-        debug_assert!(padded_size % Self::ALIGNMENT == 0);
-
-        let mut content = vec![0; padded_size].into_boxed_slice();
-        let leb128_size = leb128::write::unsigned(&mut content.as_mut(), size as u64).unwrap();
-
-        let mut result = Self {
-            leb128_size,
-            size,
-            content,
-        };
-
-        result.recalculate_checksum();
-        result
+        Self::try_new_zeroed(size).expect("padded size overflows usize")
     }
 
-    /// Creates a new `Padder` from a byte slice.
+    /// Creates a new `Padder` with a specified size, filled with zeroes, like `new_zeroed`.
     ///
     /// # Arguments
     ///
-    /// * `input` - A byte slice to be copied into the `Padder`.
+    /// * `size` - The size of the payload.
+    ///
+    /// # Returns
+    ///
+    /// * An `Option` containing a new `Padder` if the padded size fits in a usize and can be allocated.
+    /// * `None` if the padded size does not fit in a usize, or allocating it fails.
+    pub fn try_new_zeroed(size: usize) -> Option<Self> {
+        Self::new_zeroed_with_filler(size, 0)
+    }
+
+    /// Creates a new `Padder` with a specified size and filler size, filled with zeroes.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The size of the payload.
+    /// * `filler` - The size of the filler following the checksum.
+    ///
+    /// # Returns
+    ///
+    /// * An `Option` containing a new `Padder` if `filler` is a multiple of `Padder::ALIGNMENT`.
+    /// * `None` if `filler` is not a multiple of `Padder::ALIGNMENT`, or the total size does not fit in a usize or
+    ///   cannot be allocated.
+    pub fn new_zeroed_with_filler(size: usize, filler: usize) -> Option<Self> {
+        if !Mixer::is_aligned(filler) {
+            return None;
+        }
+
+        let padded_size = Self::checked_padded_size(size)?;
+
+        debug_assert!(padded_size > size);
+        debug_assert!(Mixer::is_aligned(padded_size));
+
+        let total_size = padded_size.checked_add(filler)?;
+        let mut content = Vec::new();
+        content.try_reserve_exact(total_size).ok()?;
+        content.resize(total_size, 0);
+        let mut content = content.into_boxed_slice();
+        let leb128_size = leb128::write::unsigned(&mut content.as_mut(), size as u64).unwrap();
+
+        let mut result = Self {
+            leb128_size,
+            size,
+            filler,
+            content,
+        };
+
+        result.recalculate_checksum();
+        Some(result)
+    }
+
+    /// Creates a new `Padder` from a byte slice.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A byte slice to be copied into the `Padder`.
     ///
     /// # Returns
     ///
@@ -221,6 +765,128 @@ impl Padder {
         result
     }
 
+    /// Creates a new `Padder` from a byte slice, reusing the allocation of `buf`.
+    ///
+    /// The content of `buf` is discarded and `buf` is resized to exactly the padded size of `input`. If its capacity
+    /// equals the padded size, the allocation is reused as is. Otherwise an allocation still happens: a smaller
+    /// buffer is grown, and as `Padder` holds a boxed slice, a larger one is shrunk to fit, which the allocator may
+    /// do by reallocating.
+    ///
+    /// # Arguments
+    ///
+    /// * `buf` - A buffer whose allocation is reused.
+    /// * `input` - A byte slice to be copied into the `Padder`.
+    ///
+    /// # Returns
+    ///
+    /// * A new `Padder` instance containing a copy of the input byte slice.
+    pub fn new_in(mut buf: Vec<u8>, input: &[u8]) -> Self {
+        let size = input.len();
+
+        buf.clear();
+        buf.resize(Self::padded_size(size), 0);
+        buf.shrink_to_fit();
+        let leb128_size = leb128::write::unsigned(&mut buf.as_mut_slice(), size as u64).unwrap();
+        buf[leb128_size..leb128_size + size].copy_from_slice(input);
+
+        let mut result = Self {
+            leb128_size,
+            size,
+            filler: 0,
+            content: buf.into_boxed_slice(),
+        };
+
+        result.recalculate_checksum();
+        result
+    }
+
+    /// Creates a new `Padder` from a byte slice, with a checksum field of at least `min_checksum` bytes.
+    ///
+    /// The checksum field is extended by multiples of `Padder::ALIGNMENT` bytes if it would otherwise be shorter, and
+    /// every byte of it is filled with the index-incremented CRC. Such a block is only accepted by
+    /// `try_from_raw_with_min_checksum` with the same `min_checksum`; `try_from_raw` rejects it as too long.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A byte slice to be copied into the `Padder`.
+    /// * `min_checksum` - The minimum size of the checksum field.
+    ///
+    /// # Returns
+    ///
+    /// * A new `Padder` containing the input data and a checksum field of at least `min_checksum` bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gesist::padder::Padder;
+    ///
+    /// let padder = Padder::new_with_min_checksum(b"hello", 4);
+    /// assert_eq!(padder.checksum().len(), 6);
+    ///
+    /// let raw: Box<[u8]> = padder.into();
+    /// assert_eq!(Padder::try_from_raw_with_min_checksum(raw.clone(), 4).unwrap().as_slice(), b"hello");
+    /// assert!(Padder::try_from_raw(raw).is_err());
+    /// ```
+    pub fn new_with_min_checksum(input: impl AsRef<[u8]>, min_checksum: usize) -> Self {
+        let input = input.as_ref();
+        let extended_size = Self::checked_padded_size_with_min_checksum(input.len(), min_checksum).expect("padded size overflows usize");
+        let extension = extended_size - Self::padded_size(input.len());
+
+        let mut result = Self::new_with_filler(input, extension).unwrap();
+        result.filler = 0;
+        result.recalculate_checksum();
+
+        result
+    }
+
+    /// Creates a new `Padder` from a byte slice, followed by zeroed filler of a specified size.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - A byte slice to be copied into the `Padder`.
+    /// * `filler` - The size of the filler following the checksum.
+    ///
+    /// # Returns
+    ///
+    /// * An `Option` containing a new `Padder` if `filler` is a multiple of `Padder::ALIGNMENT`.
+    /// * `None` if `filler` is not a multiple of `Padder::ALIGNMENT`.
+    pub fn new_with_filler(input: impl AsRef<[u8]>, filler: usize) -> Option<Self> {
+        let input = input.as_ref();
+        let mut result = Self::new_zeroed_with_filler(input.len(), filler)?;
+        result.as_mut().copy_from_slice(input);
+
+        Some(result)
+    }
+
+    /// Creates a `Padder` from the raw content of a padded data block and its cached sizes, without validating it.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The raw content of a padded data block, without filler.
+    /// * `leb128_size` - The size of the length field at the beginning of `content`.
+    /// * `payload_size` - The size of the payload following the length field.
+    ///
+    /// # Returns
+    ///
+    /// * A new `Padder` wrapping `content`.
+    ///
+    /// # Safety
+    ///
+    /// This method is marked as unsafe because it trusts the caller's layout, like `raw_mut`. The caller must ensure
+    /// that `content` is what `try_from_raw` would accept: its length is `Padder::padded_size(payload_size)`, it starts
+    /// with the leb128 encoding of `payload_size` in exactly `leb128_size` bytes, and the checksum matches the payload.
+    pub unsafe fn from_parts(content: Box<[u8]>, leb128_size: usize, payload_size: usize) -> Self {
+        debug_assert_eq!(content.len(), Self::padded_size(payload_size));
+        debug_assert_eq!(leb128_size, self::leb128_size(payload_size).max(1));
+
+        Self {
+            leb128_size,
+            size: payload_size,
+            filler: 0,
+            content,
+        }
+    }
+
     /// Checks if the input byte slice is a correctly padded data block.
     ///
     /// # Arguments
@@ -234,35 +900,169 @@ impl Padder {
     ///     - `Err` if the input byte slice is not a correctly padded data block, containing a `PadderCheckError`.
     pub fn try_from_raw(raw: impl Into<Box<[u8]>>) -> Result<Self, PaddingValidationError> {
         let content = raw.into();
+        let (payload_size, leb128_size, filler) = Self::validate(&content, false)?;
+
+        Ok(Self {
+            leb128_size,
+            size: payload_size,
+            filler,
+            content,
+        })
+    }
+
+    /// Checks if the input byte slice is a correctly padded data block, possibly followed by filler.
+    ///
+    /// Unlike `try_from_raw`, the input may be longer than the padded size declared by its length field, as long as
+    /// the extra bytes are a multiple of `Padder::ALIGNMENT`. The extra bytes are treated as filler and not validated.
+    ///
+    /// # Arguments
+    ///
+    /// * `raw` - A byte slice to be converted into a `Padder`.
+    ///
+    /// # Returns
+    ///
+    /// * A `Result` which is:
+    ///     - `Ok` if the input byte slice is a correctly padded data block, containing the `Padder`.
+    ///     - `Err` if the input byte slice is not a correctly padded data block, containing a `PaddingValidationError`.
+    pub fn try_from_raw_with_filler(raw: impl Into<Box<[u8]>>) -> Result<Self, PaddingValidationError> {
+        let content = raw.into();
+        let (payload_size, leb128_size, filler) = Self::validate(&content, true)?;
+
+        Ok(Self {
+            leb128_size,
+            size: payload_size,
+            filler,
+            content,
+        })
+    }
+
+    /// Checks if the input byte slice is a correctly padded data block with a checksum field of at least
+    /// `min_checksum` bytes, as created by `new_with_min_checksum`.
+    ///
+    /// The block must be exactly as long as `new_with_min_checksum` makes it for the declared payload size and
+    /// `min_checksum`, and every checksum byte is validated. With a `min_checksum` no longer than the regular checksum
+    /// field, this is the same as `try_from_raw`.
+    ///
+    /// # Arguments
+    ///
+    /// * `raw` - A byte slice to be converted into a `Padder`.
+    /// * `min_checksum` - The minimum size of the checksum field.
+    ///
+    /// # Returns
+    ///
+    /// * A `Result` which is:
+    ///     - `Ok` if the input byte slice is a correctly padded data block, containing the `Padder`.
+    ///     - `Err` if the input byte slice is not a correctly padded data block, containing a `PaddingValidationError`.
+    pub fn try_from_raw_with_min_checksum(raw: impl Into<Box<[u8]>>, min_checksum: usize) -> Result<Self, PaddingValidationError> {
+        let content = raw.into();
+        let (payload_size, leb128_size, _) = Self::validate_with_min_checksum(&content, false, min_checksum)?;
+
+        Ok(Self {
+            leb128_size,
+            size: payload_size,
+            filler: 0,
+            content,
+        })
+    }
+
+    /// Checks if the input byte slice is a correctly padded data block, repairing a truncated checksum if possible.
+    ///
+    /// If the input is exactly `ALIGNMENT` bytes shorter than the padded size declared by its length field, and the
+    /// missing bytes are all checksum bytes, they are reconstructed from the CRC of the payload. The checksum bytes
+    /// still present are validated as usual.
+    ///
+    /// This only works on unmixed padded data. A truncated *encoded* buffer cannot be repaired this way, see `Mixer`.
+    ///
+    /// This weakens integrity checking and should only be used to recover data from slightly mangled copies.
+    ///
+    /// # Arguments
+    ///
+    /// * `raw` - A byte slice to be converted into a `Padder`.
+    ///
+    /// # Returns
+    ///
+    /// * A `Result` which is:
+    ///     - `Ok` if the input byte slice is a correctly padded data block or could be repaired, containing the
+    ///       `Padder` and a flag indicating whether a repair happened.
+    ///     - `Err` if the input byte slice is neither, containing a `PaddingValidationError`.
+    pub fn try_from_raw_lenient(raw: impl Into<Box<[u8]>>) -> Result<(Self, bool), PaddingValidationError> {
+        let content = raw.into();
+
+        match Self::validate(&content, false) {
+            Ok((payload_size, leb128_size, filler)) => Ok((Self { leb128_size, size: payload_size, filler, content }, false)),
+            Err(PaddingValidationError::UnexpectedPaddedLength { payload_size, expected, actual }) if expected == actual + Self::ALIGNMENT => {
+                let (_, leb128_size) = read_length_prefix(&content)?;
+                let checksum_offset = leb128_size + payload_size;
+                if checksum_offset > actual {
+                    return Err(PaddingValidationError::UnexpectedPaddedLength { payload_size, expected, actual });
+                }
+
+                Self::validate_checksum(&content[leb128_size..checksum_offset], &content[checksum_offset..], checksum_offset)?;
+
+                let mut content = content.into_vec();
+                content.resize(expected, 0);
+
+                let mut result = Self {
+                    leb128_size,
+                    size: payload_size,
+                    filler: 0,
+                    content: content.into_boxed_slice(),
+                };
+
+                result.recalculate_checksum();
+                Ok((result, true))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Validates that `checksum` holds the index-incremented CRC of `payload`.
+    ///
+    /// `offset` is the offset of `checksum` in the padded data block, used for error reporting.
+    fn validate_checksum(payload: &[u8], checksum: &[u8], offset: usize) -> Result<(), PaddingValidationError> {
+        let expected_checksum = Self::CRC.checksum(payload);
+        for (i, &actual) in checksum.iter().enumerate() {
+            if actual != expected_checksum.wrapping_add(i as u8) {
+                return Err(PaddingValidationError::InvalidChecksum { offset: offset + i });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validates a padded data block, returning the payload size, the size of the length field and the size of the
+    /// filler.
+    ///
+    /// If `allow_filler` is false, the block must be exactly as long as the padded size declared by its length field.
+    pub(crate) fn validate(content: &[u8], allow_filler: bool) -> Result<(usize, usize, usize), PaddingValidationError> {
+        Self::validate_with_min_checksum(content, allow_filler, 0)
+    }
+
+    /// Validates a padded data block like `validate`, with the checksum field extended to at least `min_checksum`
+    /// bytes, like `new_with_min_checksum` does.
+    fn validate_with_min_checksum(content: &[u8], allow_filler: bool, min_checksum: usize) -> Result<(usize, usize, usize), PaddingValidationError> {
         let len = content.len();
 
-        if len % Self::ALIGNMENT != 0 {
+        if !Mixer::is_aligned(len) {
             return Err(PaddingValidationError::NotAligned { length: len });
         }
 
-        let mut cursor = Cursor::new(content.as_ref());
-        let payload_size = leb128::read::unsigned(&mut cursor).map_err(|_| PaddingValidationError::BadLengthField)? as usize;
-        let leb128_size = cursor.position() as usize;
+        let (payload_size, leb128_size) = read_length_prefix(content)?;
 
-        let expected_padded_size = Self::padded_size(payload_size);
-        if expected_padded_size != len {
+        let expected_padded_size = Self::checked_padded_size_with_min_checksum(payload_size, min_checksum).ok_or(PaddingValidationError::BadLengthField)?;
+        if expected_padded_size > len || (!allow_filler && expected_padded_size != len) {
             return Err(PaddingValidationError::UnexpectedPaddedLength { payload_size, expected: expected_padded_size, actual: len });
         }
 
-        let checksum_size = len - payload_size - leb128_size;
-        let expected_checksum = Self::CRC.checksum(&content[leb128_size..leb128_size + payload_size]);
-        for i in 0..checksum_size {
-            let expected = expected_checksum.wrapping_add(i as u8);
-            if content[leb128_size + payload_size + i] != expected {
-                return Err(PaddingValidationError::InvalidChecksum { offset: leb128_size + payload_size + i });
-            }
+        let checksum_offset = leb128_size + payload_size;
+        if checksum_offset > expected_padded_size {
+            return Err(PaddingValidationError::BadLengthField);
         }
 
-        Ok(Self {
-            leb128_size,
-            size: payload_size,
-            content,
-        })
+        let filler = len - expected_padded_size;
+        Self::validate_checksum(&content[leb128_size..checksum_offset], &content[checksum_offset..len - filler], checksum_offset)?;
+
+        Ok((payload_size, leb128_size, filler))
     }
 
     /// Recalculates the checksum of the `Padder`.
@@ -271,12 +1071,66 @@ impl Padder {
     ///
     /// This method is used to ensure that the checksum of the `Padder` is always correct after the `Padder` is mutated.
     pub fn recalculate_checksum(&mut self) {
-        let crc = Self::CRC.checksum(self.payload());
-        let checksum_count = self.content.len() - self.size - self.leb128_size;
+        self.write_checksum(Self::CRC.checksum(self.payload()));
+    }
+
+    /// Consumes the `Padder` and mixes it, returning the encoded data.
+    ///
+    /// This is the same as what `encode` does after padding, so after decoding into a `Padder` and editing its payload
+    /// via `as_mut`, this gives the re-encoded data in one call.
+    pub fn reencode(self) -> Box<[u8]> {
+        let mut mix = Mixer::new_from_padder(self);
+        mix.mix();
 
-        for i in 0..checksum_count {
-            self.content[self.leb128_size + self.size + i] = crc.wrapping_add(i as u8);
+        mix.into()
+    }
+
+    /// Replaces the payload of the `Padder` in place, without reallocating.
+    ///
+    /// This succeeds whenever the new payload has the same padded size as the current one, even if the lengths differ.
+    /// The length field, the payload and the checksum are rewritten, while the filler is kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `new` - The new payload.
+    ///
+    /// # Returns
+    ///
+    /// * A `Result` which is:
+    ///     - `Ok` if the payload is replaced.
+    ///     - `Err` containing `PaddingValidationError::UnexpectedPaddedLength` if the padded size of `new` differs,
+    ///       in which case the `Padder` is left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use gesist::padder::Padder;
+    ///
+    /// let mut padder = Padder::new([0; 126]);
+    /// assert!(padder.try_replace_payload(&[1; 127]).is_ok());
+    /// assert_eq!(padder.as_slice(), &[1; 127]);
+    /// assert!(padder.try_replace_payload(&[1; 128]).is_err());
+    /// ```
+    pub fn try_replace_payload(&mut self, new: &[u8]) -> Result<(), PaddingValidationError> {
+        let actual = self.content.len() - self.filler;
+        let expected = Self::padded_size(new.len());
+        if expected != actual {
+            return Err(PaddingValidationError::UnexpectedPaddedLength { payload_size: new.len(), expected, actual });
         }
+
+        self.content[..actual].fill(0);
+        self.leb128_size = leb128::write::unsigned(&mut self.content.as_mut(), new.len() as u64).unwrap();
+        self.size = new.len();
+        self.payload_mut().copy_from_slice(new);
+        self.recalculate_checksum();
+
+        Ok(())
+    }
+
+    /// Fills the checksum bytes of the `Padder` with the index-incremented `crc`.
+    fn write_checksum(&mut self, crc: u8) {
+        let checksum_end = self.content.len() - self.filler;
+        fill_checksum_from_crc(crc, &mut self.content[self.leb128_size + self.size..checksum_end]);
     }
 }
 
@@ -306,15 +1160,15 @@ impl AsRef<[u8]> for Padder {
     }
 }
 
-/// Implementation of the `Into` trait for the `Padder` struct.
-impl Into<Box<[u8]>> for Padder {
+/// Implementation of the `From` trait for converting a `Padder` into a boxed slice.
+impl From<Padder> for Box<[u8]> {
     /// Consumes the `Padder` and returns a boxed slice representing the entire content of the `Padder`.
     ///
     /// # Returns
     ///
     /// * A boxed slice representing the entire content of the `Padder`.
-    fn into(self) -> Box<[u8]> {
-        self.content
+    fn from(padder: Padder) -> Self {
+        padder.content
     }
 }
 
@@ -346,6 +1200,82 @@ impl DerefMut for PadderMutGuard<'_> {
     }
 }
 
+/// Implementation of the `Write` trait for the `PadderMutGuard` struct.
+///
+/// Bytes are written into the payload of the `Padder` sequentially, starting from the beginning of the payload. Like
+/// the implementation for `&mut [u8]`, writes stop once the payload is full, so `write_all` and `io::copy` fail with
+/// `io::ErrorKind::WriteZero` if there is more data than payload.
+impl Write for PadderMutGuard<'_> {
+    /// Writes as many bytes as fit into the remaining payload, returning the number of bytes written.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let payload = self.padder.payload_mut();
+        let count = buf.len().min(payload.len() - self.position);
+
+        payload[self.position..self.position + count].copy_from_slice(&buf[..count]);
+        self.position += count;
+
+        Ok(count)
+    }
+
+    /// Does nothing, as the bytes are written into the payload directly.
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Methods for the `PadderStreamGuard` struct.
+impl PadderStreamGuard<'_> {
+    /// Completes the checksum, feeding the payload bytes not written yet into it, and stores it in the `Padder`.
+    fn complete(&mut self) {
+        if let Some(mut digest) = self.digest.take() {
+            digest.update(&self.padder.payload()[self.position..]);
+            self.padder.write_checksum(digest.finalize());
+        }
+    }
+
+    /// Finishes writing, storing the checksum in the `Padder`.
+    ///
+    /// This is equivalent to dropping the `PadderStreamGuard`. If the whole payload has been written, it takes
+    /// constant time.
+    pub fn finish(mut self) {
+        self.complete();
+    }
+}
+
+/// Implementation of the `Write` trait for the `PadderStreamGuard` struct.
+///
+/// Like `PadderMutGuard`, bytes are written into the payload sequentially, and writes stop once the payload is full.
+impl Write for PadderStreamGuard<'_> {
+    /// Writes as many bytes as fit into the remaining payload, updating the checksum with them.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let payload = self.padder.payload_mut();
+        let count = buf.len().min(payload.len() - self.position);
+
+        payload[self.position..self.position + count].copy_from_slice(&buf[..count]);
+        self.position += count;
+
+        if let Some(digest) = self.digest.as_mut() {
+            digest.update(&buf[..count]);
+        }
+
+        Ok(count)
+    }
+
+    /// Does nothing, as the bytes are written into the payload directly.
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Implementation of the `Drop` trait for the `PadderStreamGuard` struct.
+///
+/// This ensures that the checksum of the `Padder` is stored when the `PadderStreamGuard` is dropped.
+impl Drop for PadderStreamGuard<'_> {
+    fn drop(&mut self) {
+        self.complete();
+    }
+}
+
 /// Implementation of the `Drop` trait for the `PadderMutGuard` struct.
 ///
 /// This ensures that the checksum of the `Padder` associated with the `PadderMutGuard` is
@@ -356,3 +1286,206 @@ impl Drop for PadderMutGuard<'_> {
         self.padder.recalculate_checksum();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use crate::{decode, encode};
+    use crate::padder::{Padder, PaddingValidationError};
+
+    fn raw(payload: impl AsRef<[u8]>) -> Box<[u8]> {
+        Padder::new(payload).into()
+    }
+
+    #[test]
+    fn write_into_padder() {
+        let mut padder = Padder::new_zeroed(5);
+        std::io::copy(&mut &b"hello"[..], &mut padder.as_mut()).unwrap();
+        assert_eq!(padder.as_slice(), b"hello");
+        assert!(Padder::try_from_raw(Box::<[u8]>::from(padder)).is_ok());
+
+        let mut padder = Padder::new_zeroed(4);
+        let error = padder.as_mut().write_all(b"hello").unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::WriteZero);
+    }
+
+    #[test]
+    fn stream_into_padder() {
+        let mut padder = Padder::new_zeroed(12);
+        let mut stream = padder.stream();
+        stream.write_all(b"hello, ").unwrap();
+        stream.write_all(b"world").unwrap();
+        stream.finish();
+        assert_eq!(Box::<[u8]>::from(padder), raw(b"hello, world"));
+
+        let mut padder = Padder::new(b"hello, world");
+        padder.stream().write_all(b"HELLO").unwrap();
+        assert_eq!(Box::<[u8]>::from(padder), raw(b"HELLO, world"));
+    }
+
+    #[test]
+    fn padder_regions() {
+        let padder = Padder::new([0x5a; 200]);
+        assert_eq!(padder.header().len(), 2);
+        assert_eq!(padder.checksum().len(), 2);
+        assert_eq!([padder.header(), padder.as_slice(), padder.checksum()].concat(), raw([0x5a; 200]).as_ref());
+        assert_eq!(padder.checksum_bytes_slice(), padder.checksum());
+
+        let padder = Padder::new_with_filler([0x5a; 200], 3).unwrap();
+        assert_eq!(padder.checksum_bytes_slice().len(), padder.checksum().len() + 3);
+    }
+
+    #[test]
+    fn padder_in_reused_buffer() {
+        let buf = vec![0xff; 64];
+        let padder = Padder::new_in(buf, b"hello, world");
+        assert_eq!(Box::<[u8]>::from(padder), raw(b"hello, world"));
+
+        let mut buf = Vec::with_capacity(Padder::padded_size(12));
+        buf.extend_from_slice(&[0xff; 5]);
+        let ptr = buf.as_ptr();
+        let content = Box::<[u8]>::from(Padder::new_in(buf, b"hello, world"));
+        assert_eq!(content.as_ptr(), ptr);
+        assert_eq!(content, raw(b"hello, world"));
+    }
+
+    #[test]
+    fn edit_and_reencode() {
+        let mut padder = Padder::try_from_raw(raw(b"hello")).unwrap();
+        padder.as_mut()[0] = b'j';
+        assert_eq!(padder.reencode(), encode(b"jello"));
+    }
+
+    #[test]
+    fn lenient_repairs_truncated_checksum() {
+        let block = raw(b"ab");
+        assert_eq!(block.len(), 6);
+
+        let (padder, repaired) = Padder::try_from_raw_lenient(&block[..3]).unwrap();
+        assert!(repaired);
+        assert_eq!(padder.as_slice(), b"ab");
+
+        let (_, repaired) = Padder::try_from_raw_lenient(block).unwrap();
+        assert!(!repaired);
+
+        let block = raw(b"abc");
+        assert!(Padder::try_from_raw_lenient(&block[..block.len() - 3]).is_err());
+    }
+
+    #[test]
+    fn replace_payload_in_slot() {
+        let mut padder = Padder::new_with_filler(b"hello", 3).unwrap();
+        padder.try_replace_payload(b"hello!!").unwrap();
+        assert_eq!(padder.as_slice(), b"hello!!");
+        assert_eq!(padder.filler().len(), 3);
+        assert_eq!(b"hello!!", decode(padder.clone().reencode()).unwrap().as_ref());
+
+        assert!(padder.try_replace_payload(b"hello!!!").is_err());
+        assert_eq!(padder.as_slice(), b"hello!!");
+    }
+
+    #[test]
+    fn raw_payload_edits() {
+        let mut padder = Padder::new(b"hello");
+        padder.payload_mut_raw().copy_from_slice(b"jelly");
+        assert!(decode(padder.clone().reencode()).is_err());
+
+        padder.recalculate_checksum();
+        assert_eq!(padder.reencode(), encode(b"jelly"));
+    }
+
+    #[test]
+    fn extended_checksum() {
+        let padder = Padder::new_with_min_checksum([0x5a; 100], 8);
+        assert_eq!(padder.checksum().len(), 10);
+        assert!(padder.filler().is_empty());
+
+        let encoded = padder.reencode();
+        // decode takes the extension for filler, which it does not validate
+        assert_eq!(decode(encoded.clone()).unwrap().as_ref(), [0x5a; 100]);
+
+        let mut block = Box::<[u8]>::from(crate::do_unmix(encoded).unwrap()).into_vec();
+        assert_eq!(Padder::try_from_raw_with_min_checksum(block.clone(), 8).unwrap().as_slice(), [0x5a; 100]);
+        assert_eq!(Padder::try_from_raw_with_min_checksum(block.clone(), 2).err(), Some(PaddingValidationError::UnexpectedPaddedLength { payload_size: 100, expected: 105, actual: 111 }));
+        *block.last_mut().unwrap() ^= 1;
+        assert_eq!(Padder::try_from_raw_with_min_checksum(block, 8).err(), Some(PaddingValidationError::InvalidChecksum { offset: 110 }));
+        assert_eq!(Padder::new_with_min_checksum([0x5a; 100], 1).checksum().len(), 1);
+    }
+
+    #[test]
+    fn payload_preview() {
+        use crate::padder::PayloadPreview;
+
+        assert_eq!(PayloadPreview(b"").to_string(), "[0 bytes]");
+        assert_eq!(PayloadPreview(&[0xab; 16]).to_string(), format!("[16 bytes]{}", " ab".repeat(16)));
+        assert_eq!(PayloadPreview(&[0xab; 17]).to_string(), format!("[17 bytes]{} …", " ab".repeat(16)));
+    }
+
+    #[test]
+    fn padder_from_parts() {
+        let padder = unsafe { Padder::from_parts(raw(b"hello"), 1, 5) };
+        assert_eq!(padder.as_slice(), b"hello");
+        assert_eq!(padder.reencode(), encode(b"hello"));
+
+        let padder = unsafe { Padder::from_parts(raw(b""), 1, 0) };
+        assert!(padder.as_slice().is_empty());
+        assert_eq!(Box::<[u8]>::from(padder), raw(b""));
+    }
+
+    #[test]
+    fn max_length_field() {
+        use crate::padder::{leb128_size, MAX_LEB128_LEN};
+
+        let mut buf = Vec::new();
+        leb128::write::unsigned(&mut buf, usize::MAX as u64).unwrap();
+        assert_eq!(buf.len(), MAX_LEB128_LEN);
+        assert_eq!(leb128_size(usize::MAX), MAX_LEB128_LEN);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "32")]
+    fn length_field_above_usize_is_rejected() {
+        let mut raw = Vec::new();
+        leb128::write::unsigned(&mut raw, (1 << 32) + 1).unwrap();
+        raw.extend_from_slice(&[b'a', 0, 0, 0]);
+        assert_eq!(crate::padder::read_length_prefix(&raw), Err(PaddingValidationError::BadLengthField));
+        assert!(matches!(Padder::try_from_raw(raw), Err(PaddingValidationError::BadLengthField)));
+    }
+
+    #[test]
+    fn over_long_length_field_is_rejected() {
+        use crate::mixer::Mixer;
+
+        // 30 in 4 bytes instead of 1, in a block of the padded size of 30
+        let mut raw = vec![0x9e, 0x80, 0x80, 0x00];
+        raw.resize(33, 0);
+        assert_eq!(crate::padder::read_length_prefix(&raw), Err(PaddingValidationError::BadLengthField));
+
+        let mut mix = Mixer::new(raw).unwrap();
+        mix.mix();
+        assert_eq!(decode(mix).unwrap_err(), PaddingValidationError::BadLengthField);
+    }
+
+    #[test]
+    fn error_kind_and_recoverability() {
+        use crate::padder::PaddingValidationErrorKind;
+
+        let truncated = decode(&encode(b"hello, world")[..4]).unwrap_err();
+        assert_eq!(truncated.kind(), PaddingValidationErrorKind::NotAligned);
+        assert!(truncated.is_recoverable());
+
+        let mut corrupted = encode(b"hello, world").into_vec();
+        corrupted[0] ^= 1;
+        assert!(!decode(corrupted).unwrap_err().is_recoverable());
+    }
+
+    #[test]
+    fn panic_free_constructors() {
+        assert!(Padder::try_new_zeroed(usize::MAX).is_none());
+        assert!(Padder::new_zeroed_with_filler(usize::MAX - 10, 3).is_none());
+        assert_eq!(Padder::try_new_zeroed(5).unwrap().as_slice(), [0; 5]);
+        // the padded size fits in a usize, but is too large to allocate
+        assert!(Padder::try_new_zeroed(usize::MAX / 2 + 7).is_none());
+        assert_eq!(Padder::checked_padded_size(usize::MAX - 1), None);
+    }
+}
@@ -0,0 +1,210 @@
+use std::io::{self, ErrorKind, Read};
+
+use crate::padder::PaddingValidationError;
+
+/// Reads from `r` until `buf` is full or the end of input is reached, returning the number of bytes read.
+fn read_up_to<R: Read>(r: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut read = 0;
+    while read < buf.len() {
+        match r.read(&mut buf[read..]) {
+            Ok(0) => break,
+            Ok(n) => read += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(read)
+}
+
+/// Encodes `input` and appends it to `out` as a frame, i.e. the block prefixed with its length as an unmixed leb128
+/// integer.
+///
+/// Concatenated blocks carry this length in front of each of them, as the end of a mixed block cannot be told from its
+/// beginning, see `Mixer`. Empty input encodes to an empty block, whose frame is the single byte 0.
+pub(crate) fn write_frame(out: &mut Vec<u8>, input: &[u8]) {
+    let block = crate::encode(input);
+    leb128::write::unsigned(out, block.len() as u64).unwrap();
+    out.extend_from_slice(&block);
+}
+
+/// Reads the block length at the beginning of a frame, returning `None` if `r` is at the end of input.
+///
+/// Fails with `io::ErrorKind::UnexpectedEof` if the input ends in the middle of the length, or
+/// `io::ErrorKind::InvalidData` if the length cannot be parsed or does not fit in a usize.
+pub(crate) fn read_frame_len<R: Read>(r: &mut R) -> io::Result<Option<usize>> {
+    let mut first = [0];
+    if read_up_to(r, &mut first)? == 0 {
+        return Ok(None);
+    }
+
+    let len = match leb128::read::unsigned(&mut first.chain(r)) {
+        Ok(len) => len,
+        Err(leb128::read::Error::IoError(e)) => return Err(e),
+        Err(leb128::read::Error::Overflow) => return Err(io::Error::new(ErrorKind::InvalidData, "block length overflows")),
+    };
+
+    usize::try_from(len).map(Some).map_err(|_| io::Error::new(ErrorKind::InvalidData, "block length overflows"))
+}
+
+/// Decodes the block of a frame, where an empty block decodes to an empty payload.
+pub(crate) fn decode_frame_block(block: &[u8]) -> Result<Box<[u8]>, PaddingValidationError> {
+    if block.is_empty() {
+        return Ok(Box::default());
+    }
+
+    crate::decode(block)
+}
+
+/// Reads and decodes exactly one block from a reader, leaving any data following the block in the reader.
+///
+/// The block must be framed like in `encode_stream`, i.e. prefixed with its length as an unmixed leb128 integer. The
+/// length is checked against `max_block_size` before anything else is read or allocated, so a corrupted or malicious
+/// length cannot make this allocate without bound.
+///
+/// # Arguments
+///
+/// * `r` - The reader to read the block from.
+/// * `max_block_size` - The maximum length of the block, not counting its length prefix.
+///
+/// # Returns
+///
+/// * An `io::Result` which is:
+///     - `Ok(Some(payload))` if a block is read and decoded.
+///     - `Ok(None)` if the reader is at the end of input.
+///     - `Err` if reading fails, with `io::ErrorKind::UnexpectedEof` if the input ends in the middle of a block, or
+///       with `io::ErrorKind::InvalidData` if the length prefix cannot be parsed, the block is longer than
+///       `max_block_size`, or it fails to decode, in which case the `PaddingValidationError` is the source.
+///
+/// # Example
+///
+/// ```
+/// use gesist::encode_stream;
+/// use gesist::stream::decode_one_block;
+///
+/// let blob = encode_stream(["first", "second"]);
+/// let mut reader = &blob[..];
+/// assert_eq!(decode_one_block(&mut reader, 1024).unwrap().unwrap().as_ref(), b"first");
+/// assert_eq!(decode_one_block(&mut reader, 1024).unwrap().unwrap().as_ref(), b"second");
+/// assert!(decode_one_block(&mut reader, 1024).unwrap().is_none());
+/// ```
+pub fn decode_one_block<R: Read>(r: &mut R, max_block_size: usize) -> io::Result<Option<Box<[u8]>>> {
+    let Some(len) = read_frame_len(r)? else {
+        return Ok(None);
+    };
+
+    if len > max_block_size {
+        return Err(io::Error::new(ErrorKind::InvalidData, format!("block length {} exceeds the maximum of {}", len, max_block_size)));
+    }
+
+    let mut block = vec![0; len];
+    if read_up_to(r, &mut block)? < len {
+        return Err(io::Error::new(ErrorKind::UnexpectedEof, "input ends in the middle of a block"));
+    }
+
+    decode_frame_block(&block).map(Some).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+}
+
+/// Reads a reader `frame_payload` bytes at a time and encodes each frame into its own block, so that inputs of any
+/// length can be encoded with bounded memory.
+///
+/// The last frame holds whatever the final short read returns, and no frame is yielded for an empty remainder. Each
+/// block is prefixed with its length like in `encode_stream`, so the concatenated blocks can be split and decoded
+/// with `decode_all`. The iterator ends after yielding a read error.
+///
+/// # Panics
+///
+/// Panics if `frame_payload` is 0.
+///
+/// # Example
+///
+/// ```
+/// use gesist::stream::encode_frames;
+///
+/// let blocks = encode_frames(&b"hello, world"[..], 5).collect::<std::io::Result<Vec<_>>>().unwrap();
+/// assert_eq!(blocks.len(), 3);
+///
+/// let payloads = gesist::decode_all(&blocks.concat()).unwrap();
+/// assert_eq!(payloads.concat(), b"hello, world");
+/// ```
+pub fn encode_frames<R: Read>(mut r: R, frame_payload: usize) -> impl Iterator<Item = io::Result<Box<[u8]>>> {
+    assert!(frame_payload > 0, "frame payload size must be positive");
+
+    let mut buf = vec![0; frame_payload];
+    let mut done = false;
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+
+        match read_up_to(&mut r, &mut buf) {
+            Ok(0) => {
+                done = true;
+                None
+            }
+            Ok(read) => {
+                done = read < frame_payload;
+                let mut frame = Vec::new();
+                write_frame(&mut frame, &buf[..read]);
+                Some(Ok(frame.into_boxed_slice()))
+            }
+            Err(e) => {
+                done = true;
+                Some(Err(e))
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+    use crate::encode_stream;
+    use crate::padder::PaddingValidationError;
+    use crate::stream::{decode_one_block, encode_frames};
+
+    #[test]
+    fn read_blocks_one_by_one() {
+        let mut data = encode_stream([&b"hello, world"[..], &[0x5a; 100], b"", b"!"]).into_vec();
+        data.extend_from_slice(b"tail");
+        let mut reader = data.as_slice();
+
+        assert_eq!(decode_one_block(&mut reader, 1024).unwrap().unwrap().as_ref(), b"hello, world");
+        assert_eq!(decode_one_block(&mut reader, 1024).unwrap().unwrap().as_ref(), [0x5a; 100]);
+        assert!(decode_one_block(&mut reader, 1024).unwrap().unwrap().is_empty());
+        assert_eq!(decode_one_block(&mut reader, 1024).unwrap().unwrap().as_ref(), b"!");
+        assert_eq!(decode_one_block(&mut reader, 1024).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+        assert!(decode_one_block(&mut &[][..], 1024).unwrap().is_none());
+        assert_eq!(decode_one_block(&mut &[0x80][..], 1024).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn oversized_or_invalid_blocks_are_rejected() {
+        let data = encode_stream([[0x5a; 100]]);
+        let error = decode_one_block(&mut data.as_ref(), 100).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+        // a huge declared length fails before anything is allocated
+        let mut huge = Vec::new();
+        leb128::write::unsigned(&mut huge, u64::MAX >> 1).unwrap();
+        assert_eq!(decode_one_block(&mut huge.as_slice(), 1024).unwrap_err().kind(), ErrorKind::InvalidData);
+
+        let mut corrupted = data.into_vec();
+        corrupted[1] ^= 1;
+        let error = decode_one_block(&mut corrupted.as_slice(), 1024).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(error.get_ref().unwrap().is::<PaddingValidationError>());
+    }
+
+    #[test]
+    fn frames_round_trip_through_decode_all() {
+        let input: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        let blocks = encode_frames(input.as_slice(), 300).collect::<std::io::Result<Vec<_>>>().unwrap();
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(crate::decode_all(&blocks.concat()).unwrap().concat(), input);
+
+        assert_eq!(encode_frames(&[0x5a; 600][..], 300).count(), 2);
+        assert_eq!(encode_frames(&[][..], 300).count(), 0);
+    }
+}
@@ -0,0 +1,228 @@
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use base64::Engine;
+
+/// `Output` selects the transport representation of encoded data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Output {
+    /// The raw encoded bytes.
+    Raw,
+    /// URL-safe base64 with padding, the representation used by `encode_to_base64`.
+    Base64UrlSafe,
+    /// Standard base64 with padding.
+    Base64Standard,
+    /// Lowercase hexadecimal.
+    Hex,
+    /// Uppercase hexadecimal.
+    HexUpper,
+    /// Base45 (RFC 9285), whose alphabet fits the alphanumeric mode of QR codes.
+    Base45,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub enum TransportDecodeError {
+    Base64(base64::DecodeError),
+    InvalidHexLength { length: usize },
+    InvalidHexCharacter { offset: usize, character: u8 },
+    InvalidBase45Length { length: usize },
+    InvalidBase45Character { offset: usize, character: u8 },
+    InvalidBase45Value { offset: usize },
+}
+
+impl Debug for TransportDecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransportDecodeError::Base64(e) => write!(f, "Base64 error: {}", e),
+            TransportDecodeError::InvalidHexLength { length } => write!(f, "Hex length {} is odd", length),
+            TransportDecodeError::InvalidHexCharacter { offset, character } =>
+                write!(f, "Invalid hex character {:?} at offset {}", *character as char, offset),
+            TransportDecodeError::InvalidBase45Length { length } => write!(f, "Base45 length {} leaves a single character", length),
+            TransportDecodeError::InvalidBase45Character { offset, character } =>
+                write!(f, "Invalid base45 character {:?} at offset {}", *character as char, offset),
+            TransportDecodeError::InvalidBase45Value { offset } => write!(f, "Base45 group at offset {} is out of range", offset),
+        }
+    }
+}
+
+impl Display for TransportDecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        <Self as Debug>::fmt(self, f)
+    }
+}
+
+impl Error for TransportDecodeError {}
+
+impl From<base64::DecodeError> for TransportDecodeError {
+    fn from(e: base64::DecodeError) -> Self {
+        TransportDecodeError::Base64(e)
+    }
+}
+
+const HEX_DIGITS_LOWER: &[u8; 16] = b"0123456789abcdef";
+const HEX_DIGITS_UPPER: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Returns the value of a single hex digit, accepting both cases.
+fn hex_digit_value(digit: u8) -> Option<u8> {
+    match digit {
+        b'0'..=b'9' => Some(digit - b'0'),
+        b'a'..=b'f' => Some(digit - b'a' + 10),
+        b'A'..=b'F' => Some(digit - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Encodes bytes as hexadecimal using the given digit table.
+fn hex_encode(input: &[u8], digits: &[u8; 16]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(input.len() * 2);
+    for &byte in input {
+        result.push(digits[(byte >> 4) as usize]);
+        result.push(digits[(byte & 0xf) as usize]);
+    }
+
+    result
+}
+
+/// Decodes hexadecimal in either case.
+fn hex_decode(input: &[u8]) -> Result<Vec<u8>, TransportDecodeError> {
+    if !input.len().is_multiple_of(2) {
+        return Err(TransportDecodeError::InvalidHexLength { length: input.len() });
+    }
+
+    let digit = |offset: usize| hex_digit_value(input[offset])
+        .ok_or(TransportDecodeError::InvalidHexCharacter { offset, character: input[offset] });
+
+    (0..input.len()).step_by(2).map(|i| Ok((digit(i)? << 4) | digit(i + 1)?)).collect()
+}
+
+const BASE45_DIGITS: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+/// Encodes bytes as base45, each 2 bytes into 3 characters and a trailing byte into 2 characters.
+fn base45_encode(input: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(input.len().div_ceil(2) * 3);
+    for pair in input.chunks(2) {
+        let (mut value, digits) = match *pair {
+            [a, b] => ((a as usize) << 8 | b as usize, 3),
+            [a] => (a as usize, 2),
+            _ => unreachable!(),
+        };
+
+        for _ in 0..digits {
+            result.push(BASE45_DIGITS[value % 45]);
+            value /= 45;
+        }
+    }
+
+    result
+}
+
+/// Decodes base45, as produced by `base45_encode`.
+fn base45_decode(input: &[u8]) -> Result<Vec<u8>, TransportDecodeError> {
+    if input.len() % 3 == 1 {
+        return Err(TransportDecodeError::InvalidBase45Length { length: input.len() });
+    }
+
+    let mut result = Vec::with_capacity(input.len() / 3 * 2 + 1);
+    for (group_index, group) in input.chunks(3).enumerate() {
+        let offset = group_index * 3;
+        let mut value = 0;
+        for (i, &character) in group.iter().enumerate().rev() {
+            let digit = BASE45_DIGITS.iter().position(|&d| d == character)
+                .ok_or(TransportDecodeError::InvalidBase45Character { offset: offset + i, character })?;
+            value = value * 45 + digit;
+        }
+
+        match group.len() {
+            3 if value <= 0xffff => result.extend_from_slice(&[(value >> 8) as u8, value as u8]),
+            2 if value <= 0xff => result.push(value as u8),
+            _ => return Err(TransportDecodeError::InvalidBase45Value { offset }),
+        }
+    }
+
+    Ok(result)
+}
+
+impl Output {
+    /// Converts encoded bytes into this transport representation.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The encoded bytes.
+    ///
+    /// # Returns
+    ///
+    /// * The transport representation of `data`.
+    pub fn encode(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Output::Raw => data.to_vec(),
+            Output::Base64UrlSafe => base64::prelude::BASE64_URL_SAFE.encode(data).into_bytes(),
+            Output::Base64Standard => base64::prelude::BASE64_STANDARD.encode(data).into_bytes(),
+            Output::Hex => hex_encode(data, HEX_DIGITS_LOWER),
+            Output::HexUpper => hex_encode(data, HEX_DIGITS_UPPER),
+            Output::Base45 => base45_encode(data),
+        }
+    }
+
+    /// Converts this transport representation back into encoded bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The transport representation.
+    ///
+    /// # Returns
+    ///
+    /// * A `Result` which is:
+    ///     - `Ok` containing the encoded bytes.
+    ///     - `Err` containing a `TransportDecodeError` if `data` is not valid in this representation.
+    pub fn decode(self, data: &[u8]) -> Result<Vec<u8>, TransportDecodeError> {
+        match self {
+            Output::Raw => Ok(data.to_vec()),
+            Output::Base64UrlSafe => Ok(base64::prelude::BASE64_URL_SAFE.decode(data)?),
+            Output::Base64Standard => Ok(base64::prelude::BASE64_STANDARD.decode(data)?),
+            Output::Hex | Output::HexUpper => hex_decode(data),
+            Output::Base45 => base45_decode(data),
+        }
+    }
+}
+
+/// Converts base64 from one engine's alphabet and padding to another's, without decoding the encoded data itself.
+///
+/// # Arguments
+///
+/// * `input` - The base64 to convert.
+/// * `from` - The engine `input` is encoded with.
+/// * `to` - The engine to encode the result with.
+///
+/// # Returns
+///
+/// * A `Result` which is:
+///     - `Ok` containing the base64 encoded with `to`.
+///     - `Err` containing a `base64::DecodeError` if `input` is not valid for `from`.
+///
+/// # Example
+///
+/// ```
+/// use base64::prelude::{BASE64_STANDARD, BASE64_URL_SAFE};
+/// use gesist::transport::rebase64;
+///
+/// assert_eq!(rebase64("-_-_", &BASE64_URL_SAFE, &BASE64_STANDARD).unwrap(), "+/+/");
+/// ```
+pub fn rebase64(input: &str, from: &impl Engine, to: &impl Engine) -> Result<String, base64::DecodeError> {
+    Ok(to.encode(from.decode(input)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transport::{Output, TransportDecodeError};
+
+    #[test]
+    fn base45_transport() {
+        // Test vectors from RFC 9285.
+        assert_eq!(Output::Base45.encode(b"AB"), b"BB8");
+        assert_eq!(Output::Base45.encode(b"Hello!!"), b"%69 VD92EX0");
+        assert_eq!(Output::Base45.encode(b"base-45"), b"UJCLQE7W581");
+        assert_eq!(Output::Base45.decode(b"QED8WEX0").unwrap(), b"ietf!");
+        assert_eq!(Output::Base45.decode(b"GGW"), Err(TransportDecodeError::InvalidBase45Value { offset: 0 }));
+        assert_eq!(Output::Base45.decode(b"BB8B"), Err(TransportDecodeError::InvalidBase45Length { length: 4 }));
+        assert_eq!(Output::Base45.decode(b"bB8"), Err(TransportDecodeError::InvalidBase45Character { offset: 0, character: b'b' }));
+    }
+}
@@ -0,0 +1,20 @@
+use wasm_bindgen::prelude::*;
+
+/// Encodes the input like `encode_to_base64`, for use from JavaScript.
+#[wasm_bindgen(js_name = encodeBase64)]
+pub fn encode_base64(input: &[u8]) -> String {
+    crate::encode_to_base64(input)
+}
+
+/// Decodes base64 like `decode_from_base64`, for use from JavaScript.
+///
+/// Both a `base64::DecodeError` and a `PaddingValidationError` are thrown as a JavaScript `Error` with a message
+/// telling them apart.
+#[wasm_bindgen(js_name = decodeBase64)]
+pub fn decode_base64(input: &str) -> Result<Box<[u8]>, JsValue> {
+    match crate::decode_from_base64(input) {
+        Ok(Ok(payload)) => Ok(payload),
+        Ok(Err(e)) => Err(JsError::new(&format!("Decode error: {}", e)).into()),
+        Err(e) => Err(JsError::new(&format!("Base64 error: {}", e)).into()),
+    }
+}